@@ -0,0 +1,40 @@
+import cronometro from 'cronometro'
+import { randomBytes } from 'node:crypto'
+import { createRecordHeaders, Writer } from '../src/index.ts'
+
+const headers = new Map([
+  [Buffer.from('content-type'), Buffer.from('application/json')],
+  [Buffer.from('trace-id'), randomBytes(16)],
+  [Buffer.from('source'), Buffer.from('benchmarks')]
+])
+
+const noHeaders = new Map<Buffer, Buffer>()
+
+// This is how headers were encoded before createRecordHeaders
+function encodeWithWriter (headers: Map<Buffer, Buffer>): Buffer {
+  return Writer.create().appendVarIntMap(headers, (w, [key, value]) => {
+    w.appendVarIntBytes(key).appendVarIntBytes(value)
+  }).buffer
+}
+
+cronometro(
+  {
+    /* eslint-disable-next-line @stylistic/space-before-function-paren */
+    'Writer (no headers)'() {
+      return encodeWithWriter(noHeaders)
+    },
+    /* eslint-disable-next-line @stylistic/space-before-function-paren */
+    'createRecordHeaders (no headers)'() {
+      return createRecordHeaders(noHeaders)
+    },
+    /* eslint-disable-next-line @stylistic/space-before-function-paren */
+    'Writer (3 headers)'() {
+      return encodeWithWriter(headers)
+    },
+    /* eslint-disable-next-line @stylistic/space-before-function-paren */
+    'createRecordHeaders (3 headers)'() {
+      return createRecordHeaders(headers)
+    }
+  },
+  { print: { compare: true, compareMode: 'previous' } }
+)
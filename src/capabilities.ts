@@ -0,0 +1,36 @@
+import { SASLMechanisms, type SASLMechanismValue } from './apis/enumerations.ts'
+import {
+  compressionsAlgorithms,
+  customCompressionsAlgorithms,
+  type CompressionAlgorithmValue
+} from './protocol/compression.ts'
+import { crc32cImplementation } from './protocol/crc32c.ts'
+
+export interface Capabilities {
+  compression: Record<CompressionAlgorithmValue, boolean>
+  crc32c: typeof crc32cImplementation
+  sasl: {
+    builtin: SASLMechanismValue[]
+    custom: SASLMechanismValue[]
+  }
+}
+
+// Mechanisms which can only be used by providing a custom authenticator via the sasl.authenticate option
+const customOnlySASLMechanisms: SASLMechanismValue[] = [SASLMechanisms.GSSAPI]
+
+export function capabilities (): Capabilities {
+  const compression = {} as Record<CompressionAlgorithmValue, boolean>
+
+  for (const [name, algorithm] of Object.entries({ ...compressionsAlgorithms, ...customCompressionsAlgorithms })) {
+    compression[name as CompressionAlgorithmValue] = algorithm.available ?? true
+  }
+
+  return {
+    compression,
+    crc32c: crc32cImplementation,
+    sasl: {
+      builtin: Object.values(SASLMechanisms).filter(m => !customOnlySASLMechanisms.includes(m)),
+      custom: customOnlySASLMechanisms
+    }
+  }
+}
@@ -25,7 +25,7 @@ import type { GenericError } from '../../errors.ts'
 import { MultipleErrors, NetworkError, UnsupportedApiError, UserError } from '../../errors.ts'
 import { TypedEventEmitter, type TypedEvents } from '../../events.ts'
 import { ConnectionPool, type ConnectionPoolEventPayload } from '../../network/connection-pool.ts'
-import { type Broker, type Connection } from '../../network/connection.ts'
+import { type Broker, type Connection, type SASLAuthenticationProgress } from '../../network/connection.ts'
 import { parseBroker } from '../../network/utils.ts'
 import { kInstance } from '../../symbols.ts'
 import { ajv, debugDump, loggers } from '../../utils.ts'
@@ -75,6 +75,7 @@ export interface BaseEvents extends TypedEvents {
   'client:broker:sasl:authentication:extended': (
     payload: ConnectionPoolEventPayload & { authentication: Buffer }
   ) => void
+  'client:broker:sasl:authentication:step': (payload: ConnectionPoolEventPayload & SASLAuthenticationProgress) => void
   'client:metadata': (metadata: ClusterMetadata) => void
   'client:close': () => void
 }
@@ -317,7 +318,8 @@ export class Base<
       'drain',
       'sasl:handshake',
       'sasl:authentication',
-      'sasl:authentication:extended'
+      'sasl:authentication:extended',
+      'sasl:authentication:step'
     ])
 
     return pool
@@ -52,7 +52,8 @@ export const baseOptionsSchema = {
           oneOf: [{ type: 'object', patternProperties: { '.+': { type: 'string' } } }, { function: true }]
         },
         authBytesValidator: { function: true },
-        authenticate: { function: true }
+        authenticate: { function: true },
+        timeout: { type: 'number', minimum: 0 }
       },
       required: ['mechanism'],
       additionalProperties: false
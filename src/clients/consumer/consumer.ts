@@ -60,10 +60,8 @@ import {
 import { type GenericError, NetworkError, type ProtocolError, protocolErrors, UserError } from '../../errors.ts'
 import { type ConnectionPool } from '../../network/connection-pool.ts'
 import { type Connection } from '../../network/connection.ts'
-import { INT32_SIZE } from '../../protocol/definitions.ts'
-import { Reader } from '../../protocol/reader.ts'
+import { EMPTY_BUFFER } from '../../protocol/definitions.ts'
 import { IS_CONTROL } from '../../protocol/records.ts'
-import { Writer } from '../../protocol/writer.ts'
 import { kAutocommit, kGetFetchNode, kRefreshOffsetsAndFetch } from '../../symbols.ts'
 import { emitExperimentalApiWarning } from '../../utils.ts'
 import {
@@ -101,6 +99,12 @@ import {
   listOffsetsOptionsValidator
 } from './options.ts'
 import { roundRobinAssigner } from './partitions-assigners.ts'
+import {
+  decodeConsumerProtocolAssignment,
+  decodeConsumerProtocolSubscription,
+  encodeConsumerProtocolAssignment,
+  encodeConsumerProtocolSubscription
+} from './protocol.ts'
 import { TopicsMap } from './topics-map.ts'
 import {
   type CommitOptions,
@@ -2240,70 +2244,30 @@ export class Consumer<Key = Buffer, Value = Buffer, HeaderKey = Buffer, HeaderVa
     }
   }
 
-  /*
-    The following two methods follow:
-    https://github.com/apache/kafka/blob/trunk/clients/src/main/resources/common/message/ConsumerProtocolSubscription.json
-  */
   #encodeProtocolSubscriptionMetadata (metadata: GroupProtocolSubscription, topics: string[]): Buffer {
-    return Writer.create()
-      .appendInt16(metadata.version)
-      .appendArray(topics, (w, t) => w.appendString(t, false), false, false)
-      .appendBytes(typeof metadata.metadata === 'string' ? Buffer.from(metadata.metadata) : metadata.metadata, false)
-      .buffer
+    return encodeConsumerProtocolSubscription({
+      version: metadata.version,
+      topics,
+      userData: typeof metadata.metadata === 'string' ? Buffer.from(metadata.metadata) : (metadata.metadata ?? null)
+    })
   }
 
   #decodeProtocolSubscriptionMetadata (memberId: string, buffer: Buffer): ExtendedGroupProtocolSubscription {
-    const reader = Reader.from(buffer)
+    const { version, topics, userData } = decodeConsumerProtocolSubscription(buffer)
 
-    return {
-      memberId,
-      version: reader.readInt16(),
-      topics: reader.readArray(r => r.readString(false), false, false),
-      metadata: reader.readBytes(false)
-    }
+    return { memberId, version, topics, metadata: userData ?? EMPTY_BUFFER }
   }
 
-  /*
-    The following two methods follow:
-    https://github.com/apache/kafka/blob/trunk/clients/src/main/resources/common/message/ConsumerProtocolAssignment.json
-  */
   #encodeProtocolAssignment (assignments: GroupAssignment[]): Buffer {
-    const userData = this[kOptions].assignmentUserData
-    const writer = Writer.create()
-      .appendInt16(0) // Version information
-      .appendArray(
-        assignments,
-        (w, { topic, partitions }) => {
-          w.appendString(topic, false).appendArray(partitions, (w, a) => w.appendInt32(a), false, false)
-        },
-        false,
-        false
-      )
-
-    writer.appendBytes(userData ?? null, false)
-
-    return writer.buffer
+    return encodeConsumerProtocolAssignment({
+      version: 0,
+      assignedPartitions: assignments,
+      userData: this[kOptions].assignmentUserData ?? null
+    })
   }
 
   #decodeProtocolAssignment (buffer: Buffer): GroupAssignment[] {
-    const reader = Reader.from(buffer)
-
-    reader.skip(2) // Ignore Version information
-
-    if (reader.remaining < INT32_SIZE) {
-      return []
-    }
-
-    return reader.readArray(
-      r => {
-        return {
-          topic: r.readString(false),
-          partitions: r.readArray(r => r.readInt32(), false, false)
-        }
-      },
-      false,
-      false
-    )
+    return decodeConsumerProtocolAssignment(buffer).assignedPartitions
   }
 
   #createAssignments (
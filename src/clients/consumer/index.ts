@@ -1,5 +1,7 @@
 export * from './consumer.ts'
+export * from './lag.ts'
 export * from './messages-stream.ts'
 export * from './options.ts'
+export * from './protocol.ts'
 export * from './topics-map.ts'
 export * from './types.ts'
@@ -0,0 +1,99 @@
+import { UserError } from '../../errors.ts'
+import { type Offsets } from './types.ts'
+
+export interface LagStatistics {
+  partitions: number
+  total: bigint
+  min: bigint
+  max: bigint
+  mean: number
+  percentiles: Map<number, bigint>
+}
+
+export const defaultLagPercentiles = [50, 90, 99]
+
+// Computes the lag of each partition, like getLag does, from the committed offsets. When the current positions of
+// the consumer are provided, they are used instead of the committed offsets if they are ahead, so that messages
+// already fetched but not yet committed are not counted as lag.
+// Negative committed offsets and positions denote unassigned partitions and result in -1n.
+// Offsets ahead of a stale high watermark result in no lag rather than a negative one.
+export function computeLag (
+  highWatermarks: BigInt64Array,
+  committed: BigInt64Array,
+  positions?: BigInt64Array
+): BigInt64Array {
+  if (highWatermarks.length !== committed.length || (positions && positions.length !== committed.length)) {
+    throw new UserError('High watermarks, committed offsets and positions must have the same length.')
+  }
+
+  const lag = new BigInt64Array(highWatermarks.length)
+
+  for (let i = 0; i < highWatermarks.length; i++) {
+    let offset = committed[i]
+
+    if (positions && positions[i] > offset) {
+      offset = positions[i]
+    }
+
+    if (offset < 0n) {
+      lag[i] = -1n
+    } else {
+      lag[i] = highWatermarks[i] > offset ? highWatermarks[i] - offset : 0n
+    }
+  }
+
+  return lag
+}
+
+// Negative values, which getLag uses for unassigned (-1n) or filtered (-2n) partitions, are ignored.
+export function computeLagStatistics (
+  lag: Offsets | BigInt64Array | bigint[],
+  percentiles: number[] = defaultLagPercentiles
+): LagStatistics {
+  const sources: Iterable<bigint>[] = lag instanceof Map ? Array.from(lag.values()) : [lag]
+
+  let count = 0
+  for (const source of sources) {
+    for (const value of source) {
+      if (value >= 0n) {
+        count++
+      }
+    }
+  }
+
+  // Use a typed array to sort numerically without allocating intermediate objects
+  const sorted = new BigInt64Array(count)
+  let total = 0n
+  let i = 0
+
+  for (const source of sources) {
+    for (const value of source) {
+      if (value >= 0n) {
+        sorted[i++] = value
+        total += value
+      }
+    }
+  }
+
+  sorted.sort()
+
+  const statistics: LagStatistics = {
+    partitions: count,
+    total,
+    min: count > 0 ? sorted[0] : 0n,
+    max: count > 0 ? sorted[count - 1] : 0n,
+    mean: count > 0 ? Number(total) / count : 0,
+    percentiles: new Map()
+  }
+
+  for (const percentile of percentiles) {
+    if (!Number.isFinite(percentile) || percentile <= 0 || percentile > 100) {
+      throw new UserError('Percentiles must be greater than 0 and less than or equal to 100.')
+    }
+
+    // Nearest-rank method
+    statistics.percentiles.set(percentile, count > 0 ? sorted[Math.ceil((percentile / 100) * count) - 1] : 0n)
+  }
+
+  return statistics
+}
@@ -0,0 +1,127 @@
+import { INT16_SIZE, INT32_SIZE } from '../../protocol/definitions.ts'
+import { Reader } from '../../protocol/reader.ts'
+import { Writer } from '../../protocol/writer.ts'
+import { type GroupAssignment } from './types.ts'
+
+export interface ConsumerProtocolSubscription {
+  version: number
+  topics: string[]
+  userData: Buffer | null
+  // Version 1+
+  ownedPartitions?: GroupAssignment[]
+  // Version 2+
+  generationId?: number
+  // Version 3+
+  rackId?: string | null
+}
+
+export interface ConsumerProtocolAssignment {
+  version: number
+  assignedPartitions: GroupAssignment[]
+  userData: Buffer | null
+}
+
+function appendPartitions (writer: Writer, partitions: GroupAssignment[]): void {
+  writer.appendArray(
+    partitions,
+    (w, { topic, partitions }) => {
+      w.appendString(topic, false).appendArray(partitions, (w, p) => w.appendInt32(p), false, false)
+    },
+    false,
+    false
+  )
+}
+
+function readPartitions (reader: Reader): GroupAssignment[] {
+  return reader.readArray(
+    r => {
+      return {
+        topic: r.readString(false),
+        partitions: r.readArray(r => r.readInt32(), false, false)
+      }
+    },
+    false,
+    false
+  )
+}
+
+/*
+  The following two functions follow:
+  https://github.com/apache/kafka/blob/trunk/clients/src/main/resources/common/message/ConsumerProtocolSubscription.json
+
+  Fields introduced in newer versions are only decoded when present, so that members using a newer version can be
+  decoded as well.
+*/
+export function encodeConsumerProtocolSubscription (subscription: ConsumerProtocolSubscription): Buffer {
+  const { version } = subscription
+
+  const writer = Writer.create()
+    .appendInt16(version)
+    .appendArray(subscription.topics, (w, t) => w.appendString(t, false), false, false)
+    .appendBytes(subscription.userData, false)
+
+  if (version >= 1) {
+    appendPartitions(writer, subscription.ownedPartitions ?? [])
+  }
+
+  if (version >= 2) {
+    writer.appendInt32(subscription.generationId ?? -1)
+  }
+
+  if (version >= 3) {
+    writer.appendString(subscription.rackId ?? null, false)
+  }
+
+  return writer.buffer
+}
+
+export function decodeConsumerProtocolSubscription (buffer: Buffer): ConsumerProtocolSubscription {
+  const reader = Reader.from(buffer)
+
+  const subscription: ConsumerProtocolSubscription = {
+    version: reader.readInt16(),
+    topics: reader.readArray(r => r.readString(false), false, false),
+    userData: reader.readNullableBytes(false)
+  }
+
+  if (subscription.version >= 1 && reader.remaining >= INT32_SIZE) {
+    subscription.ownedPartitions = readPartitions(reader)
+  }
+
+  if (subscription.version >= 2 && reader.remaining >= INT32_SIZE) {
+    subscription.generationId = reader.readInt32()
+  }
+
+  if (subscription.version >= 3 && reader.remaining > 0) {
+    subscription.rackId = reader.readNullableString(false)
+  }
+
+  return subscription
+}
+
+/*
+  The following two functions follow:
+  https://github.com/apache/kafka/blob/trunk/clients/src/main/resources/common/message/ConsumerProtocolAssignment.json
+*/
+export function encodeConsumerProtocolAssignment (assignment: ConsumerProtocolAssignment): Buffer {
+  const writer = Writer.create().appendInt16(assignment.version)
+  appendPartitions(writer, assignment.assignedPartitions)
+
+  return writer.appendBytes(assignment.userData, false).buffer
+}
+
+export function decodeConsumerProtocolAssignment (buffer: Buffer): ConsumerProtocolAssignment {
+  const reader = Reader.from(buffer)
+  const version = reader.remaining >= INT16_SIZE ? reader.readInt16() : 0
+
+  // Members without partitions might receive an empty assignment
+  if (reader.remaining < INT32_SIZE) {
+    return { version, assignedPartitions: [], userData: null }
+  }
+
+  return {
+    version,
+    assignedPartitions: readPartitions(reader),
+    userData: reader.remaining > 0 ? reader.readNullableBytes(false) : null
+  }
+}
@@ -1,4 +1,6 @@
+import { isUtf8 } from 'node:buffer'
 import { type Callback } from '../apis/definitions.ts'
+import { UserError } from '../errors.ts'
 import { type MessageToConsume, type MessageToProduce } from '../protocol/records.ts'
 
 export type Serializer<InputType = unknown> = (data?: InputType, metadata?: unknown) => Buffer | undefined
@@ -61,6 +63,32 @@ export function stringDeserializer (data?: string | Buffer): string | undefined
   return data.toString('utf-8')
 }
 
+// Unlike stringSerializer, this rejects strings with lone surrogates instead of silently replacing them
+export function strictStringSerializer (data?: string): Buffer | undefined {
+  if (typeof data !== 'string') {
+    return undefined
+  }
+
+  if (!data.isWellFormed()) {
+    throw new UserError('Cannot serialize a string which is not well formed UTF-16.')
+  }
+
+  return Buffer.from(data, 'utf-8')
+}
+
+// Unlike stringDeserializer, this rejects invalid UTF-8 sequences instead of silently replacing them
+export function strictStringDeserializer (data?: string | Buffer): string | undefined {
+  if (!Buffer.isBuffer(data)) {
+    return undefined
+  }
+
+  if (!isUtf8(data)) {
+    throw new UserError('Cannot deserialize a buffer which is not valid UTF-8.')
+  }
+
+  return data.toString('utf-8')
+}
+
 export function jsonSerializer<T = Record<string, any>> (data?: T): Buffer | undefined {
   return Buffer.from(JSON.stringify(data), 'utf-8')
 }
@@ -104,3 +132,5 @@ export const serdeProperties = {
 
 export const stringSerializers = serializersFrom(stringSerializer)
 export const stringDeserializers = deserializersFrom(stringDeserializer)
+export const strictStringSerializers = serializersFrom(strictStringSerializer)
+export const strictStringDeserializers = deserializersFrom(strictStringDeserializer)
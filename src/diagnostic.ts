@@ -2,6 +2,7 @@ import { channel as simpleChannel, tracingChannel, type Channel, type TracingCha
 import { type Base } from './clients/base/base.ts'
 import { type ConnectionPool } from './network/connection-pool.ts'
 import { type Connection } from './network/connection.ts'
+import { type CompressionAlgorithmValue } from './protocol/compression.ts'
 
 export type ClientType = 'base' | 'producer' | 'consumer' | 'admin'
 
@@ -20,6 +21,10 @@ export type ClientDiagnosticEvent<InstanceType extends Base = Base, Attributes =
   client: InstanceType
 } & Attributes
 
+export type CompressionDiagnosticEvent<Attributes = Record<string, unknown>> = {
+  algorithm: CompressionAlgorithmValue
+} & Attributes
+
 export type ChannelWithName<EventType extends object> = Channel<string, EventType> & { name: string }
 export type TracingChannelWithName<EventType extends object> = TracingChannel<string, EventType> & { name: string }
 
@@ -67,6 +72,10 @@ export const instancesChannel = createChannel('instances')
 export const connectionsConnectsChannel = createTracingChannel<ConnectionDiagnosticEvent>('connections:connects')
 export const connectionsApiChannel = createTracingChannel<ConnectionDiagnosticEvent>('connections:api')
 export const connectionsPoolGetsChannel = createTracingChannel<ConnectionPoolDiagnosticEvent>('connections:pool:get')
+export const connectionsSaslChannel = createTracingChannel<ConnectionDiagnosticEvent>('connections:sasl')
+
+// Protocol channels
+export const protocolCompressionsChannel = createTracingChannel<CompressionDiagnosticEvent>('protocol:compressions')
 
 // Base channels
 export const baseApisChannel = createTracingChannel<ClientDiagnosticEvent>('base:apis')
@@ -8,7 +8,13 @@ import { type Callback } from '../apis/definitions.ts'
 import { connectionsPoolGetsChannel, createDiagnosticContext, notifyCreation } from '../diagnostic.ts'
 import { MultipleErrors, NetworkError } from '../errors.ts'
 import { TypedEventEmitter, type TypedEvents } from '../events.ts'
-import { Connection, ConnectionStatuses, type Broker, type ConnectionOptions } from './connection.ts'
+import {
+  Connection,
+  ConnectionStatuses,
+  type Broker,
+  type ConnectionOptions,
+  type SASLAuthenticationProgress
+} from './connection.ts'
 
 export interface ConnectionPoolEventPayload {
   broker: Broker
@@ -22,6 +28,7 @@ export interface ConnectionPoolEvents extends TypedEvents {
   'sasl:handshake': (payload: ConnectionPoolEventPayload & { mechanisms: string[] }) => void
   'sasl:authentication': (payload: ConnectionPoolEventPayload & { authentication?: Buffer }) => void
   'sasl:authentication:extended': (payload: ConnectionPoolEventPayload & { authentication?: Buffer }) => void
+  'sasl:authentication:step': (payload: ConnectionPoolEventPayload & SASLAuthenticationProgress) => void
   disconnect: (payload: ConnectionPoolEventPayload) => void
   drain: (payload: ConnectionPoolEventPayload) => void
 }
@@ -235,6 +242,10 @@ export class ConnectionPool extends TypedEventEmitter<ConnectionPoolEvents> {
       this.emit('sasl:authentication:extended', { ...eventPayload, authentication })
     })
 
+    connection.on('sasl:authentication:step', progress => {
+      this.emit('sasl:authentication:step', { ...eventPayload, ...progress })
+    })
+
     // Remove stale connections from the pool
     connection.once('close', () => {
       this.emit('disconnect', eventPayload)
@@ -2,6 +2,7 @@ import { DynamicBuffer } from '@platformatic/dynamic-buffer'
 import fastq from 'fastq'
 import { createConnection, type NetConnectOpts, type Socket } from 'node:net'
 import { connect as createTLSConnection, type ConnectionOptions as TLSConnectionOptions } from 'node:tls'
+import { promisify } from 'node:util'
 import { type CallbackWithPromise, createPromisifiedCallback, kCallbackPromise } from '../apis/callbacks.ts'
 import { type Callback, type ResponseParser } from '../apis/definitions.ts'
 import { allowedSASLMechanisms, SASLMechanisms, type SASLMechanismValue } from '../apis/enumerations.ts'
@@ -10,12 +11,14 @@ import { type SaslAuthenticateResponse, type SASLAuthenticationAPI } from '../ap
 import {
   connectionsApiChannel,
   connectionsConnectsChannel,
+  connectionsSaslChannel,
   createDiagnosticContext,
   type DiagnosticContext,
   notifyCreation
 } from '../diagnostic.ts'
 import {
   AuthenticationError,
+  type ErrorProperties,
   type MultipleErrors,
   NetworkError,
   type ProtocolError,
@@ -43,6 +46,7 @@ export interface ConnectionEvents extends TypedEvents {
   'sasl:handshake': (mechanisms: string[]) => void
   'sasl:authentication': (authBytes?: Buffer) => void
   'sasl:authentication:extended': (authBytes?: Buffer) => void
+  'sasl:authentication:step': (progress: SASLAuthenticationProgress) => void
   drain: () => void
 }
 
@@ -51,6 +55,13 @@ export type CredentialProvider<T = string> = () => T | Promise<T>
 // @deprecated Use CredentialProvider instead
 export type SASLCredentialProvider<T> = CredentialProvider<T>
 
+export interface SASLAuthenticationProgress {
+  traceId: string
+  mechanism: SASLMechanismValue
+  step: number
+  elapsed: number
+}
+
 export interface Broker {
   host: string
   port: number
@@ -74,6 +85,7 @@ export interface SASLOptions {
   oauthBearerExtensions?: Record<string, string> | CredentialProvider<Record<string, string>>
   authenticate?: SASLCustomAuthenticator
   authBytesValidator?: (authBytes: Buffer, callback: CallbackWithPromise<Buffer>) => void
+  timeout?: number
 }
 
 export interface ConnectionOptions {
@@ -103,6 +115,13 @@ export interface Request {
   timedOut: boolean
 }
 
+type SASLDiagnosticContext = DiagnosticContext<{
+  mechanism: SASLMechanismValue
+  traceId: string
+  steps: number
+  timedOut?: boolean
+}>
+
 export const ConnectionStatuses = {
   NONE: 'none',
   CONNECTING: 'connecting',
@@ -146,6 +165,7 @@ export class Connection extends TypedEventEmitter<ConnectionEvents> {
   #socketMustBeDrained: boolean
   #detectMissingTLS: boolean
   #reauthenticationTimeout!: NodeJS.Timeout
+  #saslAuthentications: number
 
   constructor (clientId?: string, options: ConnectionOptions = {}) {
     super()
@@ -173,6 +193,7 @@ export class Connection extends TypedEventEmitter<ConnectionEvents> {
     this.#responseReader = new Reader(this.#responseBuffer)
     this.#socketMustBeDrained = false
     this.#detectMissingTLS = !this.#options.tls
+    this.#saslAuthentications = 0
 
     notifyCreation('connection', this)
   }
@@ -519,44 +540,84 @@ export class Connection extends TypedEventEmitter<ConnectionEvents> {
       this.#status = ConnectionStatuses.AUTHENTICATING
     }
 
-    const { mechanism, username, password, token, oauthBearerExtensions, authenticate } = this.#options.sasl!
+    // The trace ID allows to correlate logs and errors of concurrent handshakes to a specific connection
+    const saslDiagnosticContext = createDiagnosticContext({
+      connection: this,
+      operation: 'authenticate',
+      host,
+      port,
+      mechanism: this.#options.sasl!.mechanism,
+      reauthenticating: this.#status === ConnectionStatuses.REAUTHENTICATING,
+      traceId: `${this.#instanceId}-${++this.#saslAuthentications}`,
+      steps: 0
+    })
 
-    if (!allowedSASLMechanisms.includes(mechanism)) {
-      this.#onConnectionError(
-        host,
-        port,
-        diagnosticContext,
-        new UserError(`SASL mechanism ${mechanism} not supported.`)
+    connectionsSaslChannel.traceCallback(
+      this.#performSaslAuthentication,
+      1,
+      saslDiagnosticContext,
+      this,
+      saslDiagnosticContext,
+      this.#onSaslAuthenticate.bind(this, host, port, diagnosticContext, saslDiagnosticContext)
+    )
+  }
+
+  #performSaslAuthentication (
+    saslDiagnosticContext: SASLDiagnosticContext,
+    callback: CallbackWithPromise<SaslAuthenticateResponse>
+  ): void {
+    const { mechanism, username, password, token, oauthBearerExtensions, authenticate, timeout } = this.#options.sasl!
+    const authenticateAPI = this.#createSaslAuthenticationAPI(saslDiagnosticContext)
+
+    // Bound the whole SASL phase, as custom authenticators might never call back (e.g. when the KDC is unreachable)
+    const originalCallback = callback
+    let completed = false
+
+    const saslTimeout = setTimeout(() => {
+      saslDiagnosticContext.timedOut = true
+      callback(
+        new TimeoutError(`SASL authentication to ${this.#host}:${this.#port} timed out.`, {
+          canRetry: true,
+          saslTraceId: saslDiagnosticContext.traceId,
+          saslStep: saslDiagnosticContext.steps
+        })
       )
+    }, timeout ?? this.#options.connectTimeout)
+
+    callback = (error: Error | null, response?: SaslAuthenticateResponse) => {
+      if (completed) {
+        return
+      }
 
+      completed = true
+      clearTimeout(saslTimeout)
+      originalCallback(error, response)
+    }
+
+    if (!allowedSASLMechanisms.includes(mechanism)) {
+      callback(new UserError(`SASL mechanism ${mechanism} not supported.`))
       return
     }
 
     saslHandshakeV1.api(this, mechanism, (error, response) => {
       if (error) {
-        this.#onConnectionError(
-          host,
-          port,
-          diagnosticContext,
-          new AuthenticationError('Cannot find a suitable SASL mechanism.', { cause: error })
-        )
+        callback(new AuthenticationError('Cannot find a suitable SASL mechanism.', { cause: error }))
         return
       }
 
       this.emit('sasl:handshake', response!.mechanisms)
-      const callback = this.#onSaslAuthenticate.bind(this, host, port, diagnosticContext)
 
       if (authenticate) {
-        authenticate(mechanism, this, saslAuthenticateV2.api, username, password, token, callback)
+        authenticate(mechanism, this, authenticateAPI, username, password, token, callback)
       } else if (mechanism === SASLMechanisms.PLAIN) {
-        saslPlain.authenticate(saslAuthenticateV2.api, this, username!, password!, callback)
+        saslPlain.authenticate(authenticateAPI, this, username!, password!, callback)
       } else if (mechanism === SASLMechanisms.OAUTHBEARER) {
-        saslOAuthBearer.authenticate(saslAuthenticateV2.api, this, token!, oauthBearerExtensions!, callback)
+        saslOAuthBearer.authenticate(authenticateAPI, this, token!, oauthBearerExtensions!, callback)
       } else if (mechanism === SASLMechanisms.GSSAPI) {
         callback(new UserError('No custom SASL/GSSAPI authenticator provided.'))
       } else {
         saslScramSha.authenticate(
-          saslAuthenticateV2.api,
+          authenticateAPI,
           this,
           mechanism.substring(6) as ScramAlgorithm,
           username!,
@@ -568,6 +629,40 @@ export class Connection extends TypedEventEmitter<ConnectionEvents> {
     })
   }
 
+  #createSaslAuthenticationAPI (saslDiagnosticContext: SASLDiagnosticContext): SASLAuthenticationAPI {
+    const { traceId, mechanism } = saslDiagnosticContext
+    const startedAt = Date.now()
+
+    const api = (connection: Connection, authBytes: Buffer, callback?: Callback<SaslAuthenticateResponse>): void => {
+      const step = ++saslDiagnosticContext.steps
+
+      loggers.protocol('Sending SASL authentication step.', { traceId, mechanism, step, bytes: authBytes.length })
+
+      saslAuthenticateV2.api(connection, authBytes, (error, response) => {
+        if (error) {
+          loggers.protocol('SASL authentication step failed.', { traceId, mechanism, step, error })
+        } else {
+          loggers.protocol('Received SASL authentication step.', {
+            traceId,
+            mechanism,
+            step,
+            bytes: response!.authBytes.length
+          })
+
+          this.emit('sasl:authentication:step', { traceId, mechanism, step, elapsed: Date.now() - startedAt })
+        }
+
+        callback?.(error, response)
+      })
+    }
+
+    api.async = promisify(api) as SASLAuthenticationAPI['async']
+    api.key = saslAuthenticateV2.api.key
+    api.version = saslAuthenticateV2.api.version
+
+    return api as SASLAuthenticationAPI
+  }
+
   /*
     Request => Size [Request Header v2] [payload]
     Request Header v2 => request_api_key request_api_version correlation_id client_id TAG_BUFFER
@@ -632,14 +727,31 @@ export class Connection extends TypedEventEmitter<ConnectionEvents> {
     connectionsConnectsChannel.asyncEnd.publish(diagnosticContext)
   }
 
-  #onConnectionError (host: string, port: number, diagnosticContext: DiagnosticContext, cause: Error): void {
-    const error = new NetworkError(`Connection to ${host}:${port} failed.`, { cause })
+  #onConnectionError (
+    host: string,
+    port: number,
+    diagnosticContext: DiagnosticContext,
+    cause: Error,
+    properties: ErrorProperties = {}
+  ): void {
+    this.#onConnectionFailure(
+      diagnosticContext,
+      new NetworkError(`Connection to ${host}:${port} failed.`, { cause, ...properties })
+    )
+  }
+
+  #onConnectionFailure (diagnosticContext: DiagnosticContext, error: Error): void {
     this.#status = ConnectionStatuses.ERROR
     clearTimeout(this.#reauthenticationTimeout)
 
     diagnosticContext.error = error
     connectionsConnectsChannel.error.publish(diagnosticContext)
     connectionsConnectsChannel.asyncStart.publish(diagnosticContext)
+
+    if (error instanceof TimeoutError) {
+      this.emit('timeout', error)
+    }
+
     this.emit('error', error)
     connectionsConnectsChannel.asyncEnd.publish(diagnosticContext)
 
@@ -650,9 +762,16 @@ export class Connection extends TypedEventEmitter<ConnectionEvents> {
     host: string,
     port: number,
     diagnosticContext: DiagnosticContext,
+    saslDiagnosticContext: SASLDiagnosticContext,
     error: Error | null,
     response?: SaslAuthenticateResponse
   ): void {
+    // Timeouts of the whole authentication are reported as they are, like when connecting
+    if (saslDiagnosticContext.timedOut) {
+      this.#onConnectionFailure(diagnosticContext, error!)
+      return
+    }
+
     if (error) {
       const protocolError = (error as MultipleErrors).errors?.[0] as ProtocolError
 
@@ -660,20 +779,31 @@ export class Connection extends TypedEventEmitter<ConnectionEvents> {
         error = new AuthenticationError('SASL authentication failed.', { cause: error })
       }
 
-      this.#onConnectionError(host, port, diagnosticContext, error)
+      this.#onConnectionError(host, port, diagnosticContext, error, {
+        saslTraceId: saslDiagnosticContext.traceId,
+        saslStep: saslDiagnosticContext.steps
+      })
       return
     }
 
     if (this.#options.sasl!.authBytesValidator) {
       this.#options.sasl!.authBytesValidator(
         response!.authBytes,
-        this.#onSaslAuthenticationValidation.bind(this, host, port, diagnosticContext, response!.sessionLifetimeMs)
+        this.#onSaslAuthenticationValidation.bind(
+          this,
+          host,
+          port,
+          diagnosticContext,
+          saslDiagnosticContext,
+          response!.sessionLifetimeMs
+        )
       )
     } else {
       this.#onSaslAuthenticationValidation(
         host,
         port,
         diagnosticContext,
+        saslDiagnosticContext,
         response!.sessionLifetimeMs,
         null,
         response!.authBytes
@@ -685,6 +815,7 @@ export class Connection extends TypedEventEmitter<ConnectionEvents> {
     host: string,
     port: number,
     diagnosticContext: DiagnosticContext,
+    saslDiagnosticContext: SASLDiagnosticContext,
     sessionLifetimeMs: bigint,
     error: Error | null,
     authBytes?: Buffer
@@ -694,7 +825,8 @@ export class Connection extends TypedEventEmitter<ConnectionEvents> {
         host,
         port,
         diagnosticContext,
-        new AuthenticationError('SASL authentication failed.', { cause: error })
+        new AuthenticationError('SASL authentication failed.', { cause: error }),
+        { saslTraceId: saslDiagnosticContext.traceId, saslStep: saslDiagnosticContext.steps }
       )
       return
     }
@@ -1,10 +1,39 @@
+import { UserError } from '../errors.ts'
 import { type Broker } from './connection.ts'
 
+const bracketedPort = /^:(\d+)$/
+
 export function parseBroker (broker: Broker | string, defaultPort: number = 9092): Broker {
   if (typeof broker === 'string') {
-    if (broker.includes(':')) {
-      const [host, port] = broker.split(':')
-      return { host, port: Number(port) }
+    // IPv6 literals must be enclosed in brackets when a port is specified, as in [::1]:9092
+    if (broker.startsWith('[')) {
+      const end = broker.indexOf(']')
+
+      if (end === -1) {
+        throw new UserError(`Invalid broker "${broker}": missing closing bracket.`)
+      }
+
+      const host = broker.slice(1, end)
+      const rest = broker.slice(end + 1)
+
+      if (rest.length === 0) {
+        return { host, port: defaultPort }
+      }
+
+      const port = rest.match(bracketedPort)
+
+      if (!port) {
+        throw new UserError(`Invalid broker "${broker}": only a port can follow the closing bracket.`)
+      }
+
+      return { host, port: Number(port[1]) }
+    }
+
+    const separator = broker.lastIndexOf(':')
+
+    // More than one colon without brackets means an IPv6 literal without port
+    if (separator !== -1 && broker.indexOf(':') === separator) {
+      return { host: broker.slice(0, separator), port: Number(broker.slice(separator + 1)) }
     } else {
       return { host: broker, port: defaultPort }
     }
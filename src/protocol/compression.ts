@@ -1,9 +1,11 @@
 import { DynamicBuffer } from '@platformatic/dynamic-buffer'
 import { lz4Compress, lz4Decompress, snappyCompress, snappyDecompress } from '@platformatic/wasm-utils'
+import { type Transform } from 'node:stream'
 import zlib from 'node:zlib'
-import { UnsupportedCompressionError } from '../errors.ts'
+import { createPromisifiedCallback, kCallbackPromise, type CallbackWithPromise } from '../apis/callbacks.ts'
+import { UnsupportedCompressionError, UserError } from '../errors.ts'
 
-const { zstdCompressSync, zstdDecompressSync, gzipSync, gunzipSync } = zlib
+const { zstdCompressSync, zstdDecompressSync, gzipSync, gunzipSync, createGzip, createZstdCompress } = zlib
 
 export type SyncCompressionPhase = (data: Buffer | DynamicBuffer) => Buffer
 export type CompressionOperation = (data: Buffer) => Buffer
@@ -16,6 +18,8 @@ export interface CompressionAlgorithmSpecification {
   decompressSync: SyncCompressionPhase
   bitmask: number
   available?: boolean
+  // Worst case number of bytes the algorithm adds to data of the given size, including the framing
+  maxOverhead?: (size: number) => number
 }
 
 export const CompressionAlgorithms = {
@@ -26,9 +30,17 @@ export const CompressionAlgorithms = {
   ZSTD: 'zstd'
 } as const
 
+// Algorithms added via registerCompressionAlgorithm can be declared by merging their name in this interface
+export interface CustomCompressionAlgorithms {}
+
 export const allowedCompressionsAlgorithms = Object.values(CompressionAlgorithms) as CompressionAlgorithmValue[]
 export type CompressionAlgorithm = keyof typeof CompressionAlgorithms
-export type CompressionAlgorithmValue = (typeof CompressionAlgorithms)[keyof typeof CompressionAlgorithms]
+export type CompressionAlgorithmValue =
+  | (typeof CompressionAlgorithms)[keyof typeof CompressionAlgorithms]
+  | Extract<keyof CustomCompressionAlgorithms, string>
+
+// The compression type uses the lowest 3 bits of the record batch attributes, values from 5 to 7 are not used by Kafka
+export const customCompressionAlgorithmsBitmasks = [5, 6, 7]
 
 function ensureBuffer (data: Buffer | DynamicBuffer): Buffer {
   return DynamicBuffer.isDynamicBuffer(data) ? (data as DynamicBuffer).buffer : (data as Buffer)
@@ -68,6 +80,204 @@ function snappyDecompressSync (data: Buffer): Buffer {
   return decompressed.buffer
 }
 
+/*
+  LZ4 block format, see: https://github.com/lz4/lz4/blob/dev/doc/lz4_Block_format.md
+
+  Kafka always uses the LZ4 frame format, but some producers embed raw blocks in their payloads.
+*/
+const LZ4_FRAME_MAGIC = 0x184d2204
+const LZ4_MIN_MATCH = 4
+const LZ4_LAST_LITERALS = 5 // The last 5 bytes of a block are always literals
+const LZ4_MF_LIMIT = 12 // The last match must start at least 12 bytes before the end of the block
+const LZ4_HASH_LOG = 16
+const LZ4_MAX_OFFSET = 65535
+const LZ4_MAX_EXPANSION = 255
+
+function lz4WriteLength (output: Buffer, offset: number, length: number): number {
+  while (length >= 255) {
+    output[offset++] = 255
+    length -= 255
+  }
+
+  output[offset++] = length
+  return offset
+}
+
+export function lz4BlockCompressSync (data: Buffer | DynamicBuffer): Buffer {
+  const input = ensureBuffer(data)
+  const length = input.length
+  const output = Buffer.allocUnsafe(length + Math.ceil(length / 255) + 16)
+
+  let anchor = 0
+  let position = 0
+  let offset = 0
+
+  if (length > LZ4_MF_LIMIT) {
+    const table = new Int32Array(1 << LZ4_HASH_LOG).fill(-1)
+    const matchLimit = length - LZ4_MF_LIMIT
+    const matchEnd = length - LZ4_LAST_LITERALS
+
+    while (position < matchLimit) {
+      const sequence = input.readUInt32LE(position)
+      const hash = Math.imul(sequence, 2654435761) >>> (32 - LZ4_HASH_LOG)
+      const reference = table[hash]
+      table[hash] = position
+
+      if (reference < 0 || position - reference > LZ4_MAX_OFFSET || input.readUInt32LE(reference) !== sequence) {
+        position++
+        continue
+      }
+
+      let matchLength = LZ4_MIN_MATCH
+      while (position + matchLength < matchEnd && input[reference + matchLength] === input[position + matchLength]) {
+        matchLength++
+      }
+
+      const literals = position - anchor
+      const extraMatchLength = matchLength - LZ4_MIN_MATCH
+
+      output[offset++] = (Math.min(literals, 15) << 4) | Math.min(extraMatchLength, 15)
+
+      if (literals >= 15) {
+        offset = lz4WriteLength(output, offset, literals - 15)
+      }
+
+      offset += input.copy(output, offset, anchor, position)
+      offset = output.writeUInt16LE(position - reference, offset)
+
+      if (extraMatchLength >= 15) {
+        offset = lz4WriteLength(output, offset, extraMatchLength - 15)
+      }
+
+      position += matchLength
+      anchor = position
+    }
+  }
+
+  // Last sequence, only made of literals
+  const literals = length - anchor
+  output[offset++] = Math.min(literals, 15) << 4
+
+  if (literals >= 15) {
+    offset = lz4WriteLength(output, offset, literals - 15)
+  }
+
+  offset += input.copy(output, offset, anchor, length)
+
+  return output.subarray(0, offset)
+}
+
+// When uncompressedSize is not provided, the output buffer is grown as needed
+export function lz4BlockDecompressSync (data: Buffer | DynamicBuffer, uncompressedSize?: number): Buffer {
+  const input = ensureBuffer(data)
+  const hasSize = typeof uncompressedSize !== 'undefined'
+
+  // Each byte of a block produces at most 255 bytes, so larger sizes are always invalid
+  if (
+    hasSize &&
+    (!Number.isSafeInteger(uncompressedSize) ||
+      uncompressedSize! < 0 ||
+      uncompressedSize! > input.length * LZ4_MAX_EXPANSION)
+  ) {
+    throw new Error('Invalid LZ4 block: invalid uncompressed size')
+  }
+
+  let output = Buffer.allocUnsafe(hasSize ? uncompressedSize! : Math.max(input.length * 4, 64))
+  let position = 0
+  let offset = 0
+
+  function ensureCapacity (needed: number): void {
+    if (offset + needed <= output.length) {
+      return
+    }
+
+    if (hasSize) {
+      throw new Error('Invalid LZ4 block: decompressed data exceeds the uncompressed size')
+    }
+
+    const grown = Buffer.allocUnsafe(Math.max(output.length * 2, offset + needed))
+    output.copy(grown, 0, 0, offset)
+    output = grown
+  }
+
+  function readLength (base: number): number {
+    let length = base
+
+    if (base === 15) {
+      let byte: number
+      do {
+        if (position >= input.length) {
+          throw new Error('Invalid LZ4 block: truncated sequence length')
+        }
+
+        byte = input[position++]
+        length += byte
+      } while (byte === 255)
+    }
+
+    return length
+  }
+
+  while (position < input.length) {
+    const token = input[position++]
+    const literals = readLength(token >>> 4)
+
+    if (position + literals > input.length) {
+      throw new Error('Invalid LZ4 block: literals exceed the block length')
+    }
+
+    ensureCapacity(literals)
+    offset += input.copy(output, offset, position, position + literals)
+    position += literals
+
+    // The last sequence has no match
+    if (position >= input.length) {
+      break
+    }
+
+    if (position + 2 > input.length) {
+      throw new Error('Invalid LZ4 block: truncated match offset')
+    }
+
+    const distance = input.readUInt16LE(position)
+    position += 2
+
+    if (distance === 0 || distance > offset) {
+      throw new Error('Invalid LZ4 block: match offset out of range')
+    }
+
+    const matchLength = readLength(token & 0x0f) + LZ4_MIN_MATCH
+    ensureCapacity(matchLength)
+
+    // Matches can overlap with the data they produce, so copy byte by byte
+    for (let i = 0; i < matchLength; i++, offset++) {
+      output[offset] = output[offset - distance]
+    }
+
+    if (position >= input.length) {
+      throw new Error('Invalid LZ4 block: the last sequence must only contain literals')
+    }
+  }
+
+  if (hasSize && offset !== uncompressedSize) {
+    throw new Error('Invalid LZ4 block: decompressed data is shorter than the uncompressed size')
+  }
+
+  return output.subarray(0, offset)
+}
+
+function lz4DecompressSync (data: Buffer): Buffer {
+  if (data.length >= 4 && data.readUInt32LE(0) === LZ4_FRAME_MAGIC) {
+    return lz4DecompressFrameSync(data)
+  }
+
+  try {
+    return lz4BlockDecompressSync(data)
+  } catch (error) {
+    throw new Error('Invalid LZ4 data: not an LZ4 frame nor a valid LZ4 block', { cause: error })
+  }
+}
+
 export const compressionsAlgorithms = {
   /* c8 ignore next 8 - 'none' is actually never used but this is to please Typescript */
   none: {
@@ -87,6 +297,10 @@ export const compressionsAlgorithms = {
     decompressSync (data: Buffer | DynamicBuffer): Buffer {
       return gunzipSync(ensureBuffer(data)) as Buffer
     },
+    // See deflateBound in zlib, plus 18 bytes of gzip header and trailer
+    maxOverhead (size: number): number {
+      return (size >> 12) + (size >> 14) + (size >> 25) + 7 + 18
+    },
     bitmask: 1,
     available: true
   },
@@ -97,6 +311,10 @@ export const compressionsAlgorithms = {
     decompressSync (data: Buffer | DynamicBuffer): Buffer {
       return snappyDecompressSync(ensureBuffer(data)) as Buffer
     },
+    // See MaxCompressedLength in snappy
+    maxOverhead (size: number): number {
+      return 32 + Math.floor(size / 6)
+    },
     bitmask: 2,
     available: true
   },
@@ -105,7 +323,11 @@ export const compressionsAlgorithms = {
       return lz4CompressFrameSync(ensureBuffer(data))
     },
     decompressSync (data: Buffer | DynamicBuffer): Buffer {
-      return lz4DecompressFrameSync(ensureBuffer(data))
+      return lz4DecompressSync(ensureBuffer(data))
+    },
+    // Frame header, end mark and content checksum, plus size and checksum of each block of at least 64 KB
+    maxOverhead (size: number): number {
+      return 19 + 4 + 4 + 8 * Math.ceil(size / 65536)
     },
     bitmask: 3,
     available: true
@@ -127,6 +349,10 @@ export const compressionsAlgorithms = {
 
       return zstdDecompressSync(ensureBuffer(data))
     },
+    // See ZSTD_COMPRESSBOUND in zstd
+    maxOverhead (size: number): number {
+      return (size >> 8) + (size < 131072 ? (131072 - size) >> 11 : 0)
+    },
     bitmask: 4,
     available: typeof zstdCompressSync === 'function'
   }
@@ -134,4 +360,158 @@ export const compressionsAlgorithms = {
 
 export const compressionsAlgorithmsByBitmask = Object.fromEntries(
   Object.values(compressionsAlgorithms).map(a => [a.bitmask, a])
-)
+) as Record<number, CompressionAlgorithmSpecification>
+
+export const compressionsAlgorithmsNamesByBitmask = Object.fromEntries(
+  Object.entries(compressionsAlgorithms).map(([name, a]) => [a.bitmask, name])
+) as Record<number, CompressionAlgorithmValue>
+
+// Custom algorithms are kept apart from the builtin ones, which cannot be replaced or removed
+export const customCompressionsAlgorithms: Record<string, CompressionAlgorithmSpecification> = {}
+
+export function getCompressionAlgorithm (name: string): CompressionAlgorithmSpecification | undefined {
+  if (Object.hasOwn(compressionsAlgorithms, name)) {
+    return compressionsAlgorithms[name as keyof typeof compressionsAlgorithms]
+  }
+
+  return Object.hasOwn(customCompressionsAlgorithms, name) ? customCompressionsAlgorithms[name] : undefined
+}
+
+export function registerCompressionAlgorithm (name: string, specification: CompressionAlgorithmSpecification): void {
+  if (getCompressionAlgorithm(name)) {
+    throw new UserError(`Compression algorithm ${name} is already registered.`)
+  }
+
+  if (!customCompressionAlgorithmsBitmasks.includes(specification.bitmask)) {
+    throw new UserError(
+      `Compression algorithm bitmask must be one of ${customCompressionAlgorithmsBitmasks.join(', ')}.`
+    )
+  }
+
+  const existing = compressionsAlgorithmsNamesByBitmask[specification.bitmask]
+  if (existing) {
+    throw new UserError(`Compression algorithm bitmask ${specification.bitmask} is already used by ${existing}.`)
+  }
+
+  const algorithm = { ...specification, available: specification.available ?? true }
+
+  customCompressionsAlgorithms[name] = algorithm
+  compressionsAlgorithmsByBitmask[algorithm.bitmask] = algorithm
+  compressionsAlgorithmsNamesByBitmask[algorithm.bitmask] = name as CompressionAlgorithmValue
+  allowedCompressionsAlgorithms.push(name as CompressionAlgorithmValue)
+}
+
+export function unregisterCompressionAlgorithm (name: string): void {
+  if (!Object.hasOwn(customCompressionsAlgorithms, name)) {
+    throw new UserError(`Compression algorithm ${name} is not a registered custom algorithm.`)
+  }
+
+  const { bitmask } = customCompressionsAlgorithms[name]
+
+  delete customCompressionsAlgorithms[name]
+  delete compressionsAlgorithmsByBitmask[bitmask]
+  delete compressionsAlgorithmsNamesByBitmask[bitmask]
+  allowedCompressionsAlgorithms.splice(allowedCompressionsAlgorithms.indexOf(name as CompressionAlgorithmValue), 1)
+}
+
+export const StreamingCompressionAlgorithms = {
+  GZIP: 'gzip',
+  ZSTD: 'zstd'
+} as const
+export type StreamingCompressionAlgorithmValue =
+  (typeof StreamingCompressionAlgorithms)[keyof typeof StreamingCompressionAlgorithms]
+
+// Compresses data pushed in chunks, so that large values never need to be fully buffered before compression.
+// The compressed data can be consumed as it is produced via read(), while finish() returns the remaining part.
+// The result is a standalone gzip or zstd payload, unrelated to the compression of record batches.
+export class StreamingCompressor {
+  #stream: Transform
+  #chunks: Buffer[]
+  #error: Error | null
+  #finished: boolean
+
+  constructor (algorithm: StreamingCompressionAlgorithmValue) {
+    if (algorithm === StreamingCompressionAlgorithms.GZIP) {
+      this.#stream = createGzip()
+    } else if (algorithm === StreamingCompressionAlgorithms.ZSTD) {
+      /* c8 ignore next 3 - Tests are only run on Node.js versions that support zstd */
+      if (typeof createZstdCompress !== 'function') {
+        throw new UnsupportedCompressionError('zstd is not supported in the current Node.js version')
+      }
+
+      this.#stream = createZstdCompress()
+    } else {
+      throw new UnsupportedCompressionError(`Unsupported streaming compression algorithm ${algorithm}`)
+    }
+
+    this.#chunks = []
+    this.#error = null
+    this.#finished = false
+
+    this.#stream.on('data', chunk => this.#chunks.push(chunk))
+    this.#stream.on('error', error => {
+      this.#error = error
+    })
+  }
+
+  // Returns false when the caller should wait for the drain event before pushing more data
+  push (chunk: Buffer | DynamicBuffer): boolean {
+    if (this.#finished) {
+      throw new UserError('Cannot push data to a finished compressor.')
+    }
+
+    return this.#stream.write(ensureBuffer(chunk))
+  }
+
+  // Returns the compressed data produced so far and not returned yet, so that it can be forwarded incrementally
+  read (): Buffer {
+    if (this.#error) {
+      throw this.#error
+    }
+
+    const data = Buffer.concat(this.#chunks)
+    this.#chunks = []
+
+    return data
+  }
+
+  once (event: 'drain', listener: () => void): this {
+    this.#stream.once(event, listener)
+    return this
+  }
+
+  finish (callback: CallbackWithPromise<Buffer>): void
+  finish (): Promise<Buffer>
+  finish (callback?: CallbackWithPromise<Buffer>): void | Promise<Buffer> {
+    if (!callback) {
+      callback = createPromisifiedCallback<Buffer>()
+    }
+
+    if (this.#finished) {
+      callback(new UserError('Compressor has already been finished.'))
+      return callback[kCallbackPromise]
+    }
+
+    this.#finished = true
+
+    if (this.#error) {
+      callback(this.#error)
+      return callback[kCallbackPromise]
+    }
+
+    // Wait for the readable side to end, so that all the compressed data has been collected
+    this.#stream.once('error', callback)
+    this.#stream.once('end', () => {
+      this.#stream.off('error', callback!)
+      callback!(null, Buffer.concat(this.#chunks))
+    })
+
+    this.#stream.end()
+
+    return callback[kCallbackPromise]
+  }
+}
+
+export function createStreamingCompressor (algorithm: StreamingCompressionAlgorithmValue): StreamingCompressor {
+  return new StreamingCompressor(algorithm)
+}
@@ -101,5 +101,76 @@ export function jsCRC32C (data: Buffer | Uint8Array | DynamicBuffer): number {
   return (crc ^ 0xffffffff) >>> 0
 }
 
-/* c8 ignore next - Hard to test */
-export const crc32c = loadNativeCRC32C() ?? wasmCRC32C
+// Based on crc32_combine from zlib, using the reversed Castagnoli polynomial
+const CRC32C_POLYNOMIAL = 0x82f63b78
+
+function gf2MatrixTimes (matrix: Uint32Array, vector: number): number {
+  let sum = 0
+
+  for (let i = 0; vector !== 0; i++, vector >>>= 1) {
+    if (vector & 1) {
+      sum ^= matrix[i]
+    }
+  }
+
+  return sum >>> 0
+}
+
+function gf2MatrixSquare (square: Uint32Array, matrix: Uint32Array): void {
+  for (let i = 0; i < 32; i++) {
+    square[i] = gf2MatrixTimes(matrix, matrix[i])
+  }
+}
+
+// Computes the CRC32C of the concatenation of two chunks given their CRC32C and the length of the second one
+export function crc32cCombine (crcA: number, crcB: number, lengthB: number): number {
+  if (lengthB <= 0) {
+    return crcA >>> 0
+  }
+
+  const even = new Uint32Array(32) // Operator for an even number of zero bits
+  const odd = new Uint32Array(32) // Operator for an odd number of zero bits
+
+  // Operator for a single zero bit
+  odd[0] = CRC32C_POLYNOMIAL
+  for (let i = 1, row = 1; i < 32; i++, row <<= 1) {
+    odd[i] = row
+  }
+
+  gf2MatrixSquare(even, odd) // Two zero bits
+  gf2MatrixSquare(odd, even) // Four zero bits
+
+  // Apply lengthB zero bytes to crcA, squaring the operator at each step
+  let crc = crcA >>> 0
+  let length = lengthB
+
+  while (length > 0) {
+    gf2MatrixSquare(even, odd)
+
+    if (length % 2 === 1) {
+      crc = gf2MatrixTimes(even, crc)
+    }
+
+    length = Math.floor(length / 2)
+
+    if (length === 0) {
+      break
+    }
+
+    gf2MatrixSquare(odd, even)
+
+    if (length % 2 === 1) {
+      crc = gf2MatrixTimes(odd, crc)
+    }
+
+    length = Math.floor(length / 2)
+  }
+
+  return (crc ^ crcB) >>> 0
+}
+
+const nativeCRC32C = loadNativeCRC32C()
+
+/* c8 ignore next 2 - Hard to test */
+export const crc32c = nativeCRC32C ?? wasmCRC32C
+export const crc32cImplementation: 'native' | 'wasm' = nativeCRC32C ? 'native' : 'wasm'
@@ -6,6 +6,7 @@ export * from './errors.ts'
 export * from './murmur2.ts'
 export * from './reader.ts'
 export * from './records.ts'
+export * as saslGssapi from './sasl/gssapi.ts'
 export * as saslOAuthBearer from './sasl/oauth-bearer.ts'
 export * as saslPlain from './sasl/plain.ts'
 export * as saslScramSha from './sasl/scram-sha.ts'
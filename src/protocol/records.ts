@@ -1,11 +1,12 @@
 import { DynamicBuffer } from '@platformatic/dynamic-buffer'
-import { UnsupportedCompressionError } from '../errors.ts'
+import { createDiagnosticContext, protocolCompressionsChannel } from '../diagnostic.ts'
+import { UnsupportedCompressionError, UserError } from '../errors.ts'
 import type { NumericMap } from '../utils.ts'
 import {
-  type CompressionAlgorithmSpecification,
   type CompressionAlgorithmValue,
-  compressionsAlgorithms,
-  compressionsAlgorithmsByBitmask
+  compressionsAlgorithmsByBitmask,
+  compressionsAlgorithmsNamesByBitmask,
+  getCompressionAlgorithm
 } from './compression.ts'
 import { crc32c } from './crc32c.ts'
 import { INT32_SIZE, INT64_SIZE, type NullableString } from './definitions.ts'
@@ -18,6 +19,8 @@ export const IS_COMPRESSED = 0b111 // Bits 0, 1 and/or 2 set
 export const IS_TRANSACTIONAL = 1 << 4 // Bit 4 set
 export const IS_CONTROL = 1 << 5 // Bits 5 set
 export const BATCH_HEAD = INT64_SIZE + INT32_SIZE // FirstOffset + Length
+// All the fields of a batch before the records, including the number of records
+export const BATCH_OVERHEAD = 61
 
 export interface MessageBase<Key = Buffer, Value = Buffer> {
   key?: Key
@@ -77,6 +80,16 @@ export interface MessageRecord {
   timestamp?: bigint
 }
 
+// Flat list of header keys and values: [key1, value1, key2, value2, ...]
+export type RecordHeadersPairs = (Buffer | null)[]
+
+export interface BatchSizeEstimate {
+  // Exact size of the batch when not compressed
+  size: number
+  // Size of the batch assuming the records compress by the given ratio, plus the worst case overhead of the algorithm
+  compressedSize: number
+}
+
 export interface CreateRecordsBatchOptions {
   transactionalId?: NullableString
   compression: CompressionAlgorithmValue
@@ -178,6 +191,111 @@ export const messageSchema = {
   additionalProperties: true
 }
 
+function sizeOfVarInt (value: number): number {
+  let zigzag = ((value << 1) ^ (value >> 31)) >>> 0
+  let size = 1
+
+  while (zigzag >= 0x80) {
+    zigzag >>>= 7
+    size++
+  }
+
+  return size
+}
+
+function sizeOfVarInt64 (value: bigint): number {
+  let zigzag = ((value << 1n) ^ (value >> 63n)) & 0xffffffffffffffffn
+  let size = 1
+
+  while (zigzag >= 0x80n) {
+    zigzag >>= 7n
+    size++
+  }
+
+  return size
+}
+
+function sizeOfVarIntBytes (value: Buffer | null | undefined): number {
+  return value == null ? sizeOfVarInt(-1) : sizeOfVarInt(value.length) + value.length
+}
+
+function toRecordHeadersPairs (
+  headers: Map<Buffer, Buffer> | RecordHeadersPairs | null | undefined
+): RecordHeadersPairs {
+  if (Array.isArray(headers)) {
+    if (headers.length % 2 !== 0) {
+      throw new UserError('Record headers must contain an even number of keys and values.')
+    }
+
+    return headers
+  }
+
+  const pairs: RecordHeadersPairs = []
+
+  if (headers) {
+    for (const [key, value] of headers) {
+      pairs.push(key, value)
+    }
+  }
+
+  return pairs
+}
+
+function sizeOfRecordHeaders (pairs: RecordHeadersPairs): number {
+  let size = sizeOfVarInt(pairs.length / 2)
+
+  for (const entry of pairs) {
+    size += sizeOfVarIntBytes(entry)
+  }
+
+  return size
+}
+
+function writeVarInt (buffer: Buffer, value: number, offset: number): number {
+  let zigzag = ((value << 1) ^ (value >> 31)) >>> 0
+
+  while (zigzag >= 0x80) {
+    buffer[offset++] = (zigzag & 0x7f) | 0x80
+    zigzag >>>= 7
+  }
+
+  buffer[offset++] = zigzag
+  return offset
+}
+
+function writeVarIntBytes (buffer: Buffer, value: Buffer | null, offset: number): number {
+  if (value == null) {
+    return writeVarInt(buffer, -1, offset)
+  }
+
+  offset = writeVarInt(buffer, value.length, offset)
+  return offset + value.copy(buffer, offset)
+}
+
+// Most records have no headers, so their encoding (a zero count) is shared and must never be modified
+const EMPTY_RECORD_HEADERS = Buffer.from([0])
+
+function hasRecordHeaders (headers: Map<Buffer, Buffer> | RecordHeadersPairs | null | undefined): boolean {
+  return !!headers && (Array.isArray(headers) ? headers.length > 0 : headers.size > 0)
+}
+
+// Encodes the headers of a record in a single buffer, computing its size upfront
+export function createRecordHeaders (headers: Map<Buffer, Buffer> | RecordHeadersPairs | null | undefined): Buffer {
+  if (!hasRecordHeaders(headers)) {
+    return EMPTY_RECORD_HEADERS
+  }
+
+  const pairs = toRecordHeadersPairs(headers)
+  const buffer = Buffer.allocUnsafe(sizeOfRecordHeaders(pairs))
+  let offset = writeVarInt(buffer, pairs.length / 2, 0)
+
+  for (const entry of pairs) {
+    offset = writeVarIntBytes(buffer, entry, offset)
+  }
+
+  return buffer
+}
+
 export function createRecord (message: MessageRecord, offsetDelta: number, firstTimestamp: bigint): Writer {
   return Writer.create()
     .appendInt8(0) // Attributes are unused for now
@@ -185,9 +303,7 @@ export function createRecord (message: MessageRecord, offsetDelta: number, first
     .appendVarInt(offsetDelta)
     .appendVarIntBytes(message.key)
     .appendVarIntBytes(message.value)
-    .appendVarIntMap(message.headers, (w, [key, value]) => {
-      w.appendVarIntBytes(key).appendVarIntBytes(value)
-    })
+    .append(createRecordHeaders(message.headers))
     .prependVarIntLength()
 }
 
@@ -244,9 +360,7 @@ export function createRecordsBatch (
 
   // Set the compression, if any
   if ((options.compression ?? 'none') !== 'none') {
-    const algorithm = compressionsAlgorithms[
-      options.compression as keyof typeof compressionsAlgorithms
-    ] as CompressionAlgorithmSpecification
+    const algorithm = getCompressionAlgorithm(options.compression!)
 
     if (!algorithm) {
       throw new UnsupportedCompressionError(`Unsupported compression algorithm ${options.compression}`)
@@ -254,7 +368,12 @@ export function createRecordsBatch (
 
     attributes |= algorithm.bitmask
 
-    const compressed = algorithm.compressSync(buffer.buffer)
+    const compressed = protocolCompressionsChannel.traceSync(
+      algorithm.compressSync,
+      createDiagnosticContext({ algorithm: options.compression!, operation: 'compress', size: buffer.length }),
+      algorithm,
+      buffer.buffer
+    )
     buffer = new DynamicBuffer(compressed)
   }
 
@@ -283,6 +402,59 @@ export function createRecordsBatch (
   )
 }
 
+// Computes the size of the batch createRecordsBatch would create, without encoding it.
+// The compression ratio depends on the data and is therefore required when using compression: a ratio of 1
+// gives an upper bound of the compressed size, since the worst case overhead of the algorithm is always added.
+export function estimateBatchSize (messages: MessageRecord[], compression?: 'none'): BatchSizeEstimate
+export function estimateBatchSize (
+  messages: MessageRecord[],
+  compression: CompressionAlgorithmValue,
+  compressionRatio: number
+): BatchSizeEstimate
+export function estimateBatchSize (
+  messages: MessageRecord[],
+  compression: CompressionAlgorithmValue = 'none',
+  compressionRatio?: number
+): BatchSizeEstimate {
+  const now = BigInt(Date.now())
+  const firstTimestamp = messages[0]?.timestamp ?? now
+  let recordsSize = 0
+
+  for (let i = 0; i < messages.length; i++) {
+    const message = messages[i]
+
+    const length =
+      1 + // Attributes
+      sizeOfVarInt64(BigInt(message.timestamp ?? now) - BigInt(firstTimestamp)) +
+      sizeOfVarInt(i) +
+      sizeOfVarIntBytes(message.key) +
+      sizeOfVarIntBytes(message.value) +
+      (hasRecordHeaders(message.headers) ? sizeOfRecordHeaders(toRecordHeadersPairs(message.headers)) : 1)
+
+    recordsSize += sizeOfVarInt(length) + length
+  }
+
+  const size = BATCH_OVERHEAD + recordsSize
+
+  if (compression === 'none') {
+    return { size, compressedSize: size }
+  }
+
+  const algorithm = getCompressionAlgorithm(compression)
+
+  if (!algorithm) {
+    throw new UnsupportedCompressionError(`Unsupported compression algorithm ${compression}`)
+  }
+
+  if (typeof compressionRatio !== 'number' || !Number.isFinite(compressionRatio) || compressionRatio <= 0) {
+    throw new UserError('Compression ratio must be a positive number when using compression.')
+  }
+
+  const overhead = algorithm.maxOverhead?.(recordsSize) ?? 0
+
+  return { size, compressedSize: BATCH_OVERHEAD + Math.ceil(recordsSize * compressionRatio) + overhead }
+}
+
 // TODO: Early bail out if there are not enough bytes to read all the records as it might be truncated
 export function readRecordsBatch (reader: Reader): RecordsBatch {
   const initialPosition = reader.position
@@ -315,7 +487,16 @@ export function readRecordsBatch (reader: Reader): RecordsBatch {
     // The length of all headers immediately following Length up to the length of the Records array
     const headersLength = reader.position - initialPosition - INT32_SIZE - INT64_SIZE
     const compressedDataLen = batch.length - headersLength
-    const buffer = algorithm.decompressSync(reader.buffer.slice(reader.position, reader.position + compressedDataLen))
+    const buffer = protocolCompressionsChannel.traceSync(
+      algorithm.decompressSync,
+      createDiagnosticContext({
+        algorithm: compressionsAlgorithmsNamesByBitmask[compression],
+        operation: 'decompress',
+        size: compressedDataLen
+      }),
+      algorithm,
+      reader.buffer.slice(reader.position, reader.position + compressedDataLen)
+    )
 
     // Move the original reader to the end of the compressed data
     reader.skip(compressedDataLen)
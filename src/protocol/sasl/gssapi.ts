@@ -0,0 +1,157 @@
+import { createPromisifiedCallback, kCallbackPromise, type CallbackWithPromise } from '../../apis/callbacks.ts'
+import { type SASLAuthenticationAPI, type SaslAuthenticateResponse } from '../../apis/security/sasl-authenticate-v2.ts'
+import { AuthenticationError } from '../../errors.ts'
+import { type Connection } from '../../network/connection.ts'
+import { EMPTY_BUFFER } from '../definitions.ts'
+
+export type GSSAPIClientCallback = (error: Error | string | null | undefined, response?: string) => void
+
+// This matches the KerberosClient interface of the kerberos package. All tokens are base64 encoded.
+export interface GSSAPIClient {
+  contextComplete: boolean
+  step (challenge: string, callback: GSSAPIClientCallback): void
+  unwrap (challenge: string, callback: GSSAPIClientCallback): void
+  wrap (challenge: string, options: Record<string, unknown>, callback: GSSAPIClientCallback): void
+}
+
+export const GSSAPIStages = {
+  HANDSHAKE: 'handshake',
+  SECURITY_LAYER: 'security-layer'
+} as const
+export type GSSAPIStage = (typeof GSSAPIStages)[keyof typeof GSSAPIStages]
+
+// The GSSAPI client method or the broker request which failed
+export type GSSAPIOperation = 'step' | 'unwrap' | 'wrap' | 'authenticate'
+
+// Byte 0: No security layer; Byte 1-3: max message size - 0=none
+const SECURITY_LAYER_NONE = Buffer.from([1, 0, 0, 0]).toString('base64')
+
+function createKerberosAuthenticationError (
+  message: string,
+  kerberosError: Error | string,
+  gssapiStage: GSSAPIStage,
+  gssapiOperation: GSSAPIOperation
+): AuthenticationError {
+  return new AuthenticationError(message, {
+    cause: kerberosError instanceof Error ? kerberosError : undefined,
+    kerberosError,
+    gssapiStage,
+    gssapiOperation
+  })
+}
+
+function createBrokerAuthenticationError (cause: Error, gssapiStage: GSSAPIStage): AuthenticationError {
+  return new AuthenticationError('SASL authentication failed.', { cause, gssapiStage, gssapiOperation: 'authenticate' })
+}
+
+function performSecurityLayerNegotiation (
+  authenticateAPI: SASLAuthenticationAPI,
+  connection: Connection,
+  client: GSSAPIClient,
+  response: SaslAuthenticateResponse,
+  callback: CallbackWithPromise<SaslAuthenticateResponse>
+): void {
+  client.unwrap(response.authBytes.toString('base64'), error => {
+    if (error) {
+      callback(
+        createKerberosAuthenticationError(
+          'Cannot unwrap Kerberos response.',
+          error,
+          GSSAPIStages.SECURITY_LAYER,
+          'unwrap'
+        )
+      )
+      return
+    }
+
+    client.wrap(SECURITY_LAYER_NONE, {}, (error, wrapped) => {
+      if (error) {
+        callback(
+          createKerberosAuthenticationError(
+            'Cannot wrap Kerberos response.',
+            error,
+            GSSAPIStages.SECURITY_LAYER,
+            'wrap'
+          )
+        )
+        return
+      }
+
+      authenticateAPI(connection, Buffer.from(wrapped!, 'base64'), (error, response) => {
+        if (error) {
+          callback(createBrokerAuthenticationError(error, GSSAPIStages.SECURITY_LAYER))
+          return
+        }
+
+        callback(null, response)
+      })
+    })
+  })
+}
+
+function performChallenge (
+  authenticateAPI: SASLAuthenticationAPI,
+  connection: Connection,
+  client: GSSAPIClient,
+  challenge: string,
+  callback: CallbackWithPromise<SaslAuthenticateResponse>
+): void {
+  client.step(challenge, (error, token) => {
+    if (error) {
+      callback(
+        createKerberosAuthenticationError(
+          'Cannot continue Kerberos step challenge.',
+          error,
+          GSSAPIStages.HANDSHAKE,
+          'step'
+        )
+      )
+      return
+    }
+
+    authenticateAPI(connection, token ? Buffer.from(token, 'base64') : EMPTY_BUFFER, (error, response) => {
+      if (error) {
+        callback(createBrokerAuthenticationError(error, GSSAPIStages.HANDSHAKE))
+        return
+      }
+
+      if (response!.authBytes.length === 0) {
+        callback(null, response)
+        return
+      }
+
+      if (client.contextComplete) {
+        performSecurityLayerNegotiation(authenticateAPI, connection, client, response!, callback)
+        return
+      }
+
+      performChallenge(authenticateAPI, connection, client, response!.authBytes.toString('base64'), callback)
+    })
+  })
+}
+
+export function authenticate (
+  authenticateAPI: SASLAuthenticationAPI,
+  connection: Connection,
+  client: GSSAPIClient,
+  callback: CallbackWithPromise<SaslAuthenticateResponse>
+): void
+export function authenticate (
+  authenticateAPI: SASLAuthenticationAPI,
+  connection: Connection,
+  client: GSSAPIClient
+): Promise<SaslAuthenticateResponse>
+export function authenticate (
+  authenticateAPI: SASLAuthenticationAPI,
+  connection: Connection,
+  client: GSSAPIClient,
+  callback?: CallbackWithPromise<SaslAuthenticateResponse>
+): void | Promise<SaslAuthenticateResponse> {
+  if (!callback) {
+    callback = createPromisifiedCallback<SaslAuthenticateResponse>()
+  }
+
+  performChallenge(authenticateAPI, connection, client, '', callback)
+
+  return callback[kCallbackPromise]
+}
@@ -19,8 +19,18 @@ export interface DataValidationContext {
 
 export type DebugDumpLogger = (...args: any[]) => void
 
+export interface NameViolation {
+  index: number
+  name: string
+  reason: string
+}
+
 export { setTimeout as sleep } from 'node:timers/promises'
 
+// See: org.apache.kafka.common.internals.Topic in the Kafka source code
+export const MAX_TOPIC_NAME_LENGTH = 249
+const topicNameCharacters = /^[a-zA-Z0-9._-]+$/
+
 export const ajv = new Ajv2020({ allErrors: true, coerceTypes: false, strict: true })
 
 export const loggers: Record<string, debug.Debugger> = {
@@ -196,3 +206,55 @@ export function emitExperimentalApiWarning (api: string): void {
     `The ${api} API is experimental and does not follow semver. It may change in minor/patch releases.`
   )
 }
+
+export function validateTopicNames (names: string[]): NameViolation[] {
+  const violations: NameViolation[] = []
+  // Kafka uses topic names in metrics names, where '.' and '_' are equivalent
+  const normalized = new Map<string, number>()
+
+  for (let index = 0; index < names.length; index++) {
+    const name = names[index]
+    let reason: string | undefined
+
+    if (typeof name !== 'string' || name.length === 0) {
+      reason = 'Topic name cannot be empty.'
+    } else if (name === '.' || name === '..') {
+      reason = 'Topic name cannot be "." or "..".'
+    } else if (name.length > MAX_TOPIC_NAME_LENGTH) {
+      reason = `Topic name cannot be longer than ${MAX_TOPIC_NAME_LENGTH} characters.`
+    } else if (!topicNameCharacters.test(name)) {
+      reason = 'Topic name can only contain ASCII alphanumerics, ".", "_" and "-".'
+    } else {
+      const key = name.replaceAll('.', '_')
+      const existing = normalized.get(key)
+
+      if (typeof existing === 'undefined') {
+        normalized.set(key, index)
+      } else if (names[existing] === name) {
+        reason = `Topic name is duplicated at index ${existing}.`
+      } else {
+        reason = `Topic name collides with "${names[existing]}" since "." and "_" are equivalent in metrics names.`
+      }
+    }
+
+    if (reason) {
+      violations.push({ index, name, reason })
+    }
+  }
+
+  return violations
+}
+
+export function validateGroupIds (ids: string[]): NameViolation[] {
+  const violations: NameViolation[] = []
+
+  for (let index = 0; index < ids.length; index++) {
+    const name = ids[index]
+
+    if (typeof name !== 'string' || name.length === 0) {
+      violations.push({ index, name, reason: 'Group ID cannot be empty.' })
+    }
+  }
+
+  return violations
+}
@@ -0,0 +1,34 @@
+import { deepStrictEqual, strictEqual } from 'node:assert'
+import { test } from 'node:test'
+import zlib from 'node:zlib'
+import { capabilities, crc32cImplementation, SASLMechanisms } from '../src/index.ts'
+
+test('capabilities should report the available features', () => {
+  const { compression, crc32c, sasl } = capabilities()
+
+  deepStrictEqual(compression, {
+    none: true,
+    gzip: true,
+    snappy: true,
+    lz4: true,
+    zstd: typeof zlib.zstdCompressSync === 'function'
+  })
+
+  strictEqual(crc32c, crc32cImplementation)
+  deepStrictEqual(sasl, {
+    builtin: [
+      SASLMechanisms.PLAIN,
+      SASLMechanisms.SCRAM_SHA_256,
+      SASLMechanisms.SCRAM_SHA_512,
+      SASLMechanisms.OAUTHBEARER
+    ],
+    custom: [SASLMechanisms.GSSAPI]
+  })
+})
+
+test('capabilities should return a new object on each call', () => {
+  const first = capabilities()
+  first.compression.gzip = false
+
+  strictEqual(capabilities().compression.gzip, true)
+})
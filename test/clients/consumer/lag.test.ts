@@ -0,0 +1,90 @@
+import { deepStrictEqual, throws } from 'node:assert'
+import test from 'node:test'
+import { computeLag, computeLagStatistics, UserError } from '../../../src/index.ts'
+
+test('computeLag should compute the lag of each partition', () => {
+  const highWatermarks = BigInt64Array.from([100n, 200n, 300n, 400n])
+  const committed = BigInt64Array.from([90n, -1n, 300n, 0n])
+
+  deepStrictEqual(computeLag(highWatermarks, committed), BigInt64Array.from([10n, -1n, 0n, 400n]))
+})
+
+test('computeLag should use the current positions when ahead of the committed offsets', () => {
+  const highWatermarks = BigInt64Array.from([100n, 200n, 300n, 400n])
+  const committed = BigInt64Array.from([90n, -1n, -1n, 50n])
+  const positions = BigInt64Array.from([95n, 150n, -1n, 40n])
+
+  deepStrictEqual(computeLag(highWatermarks, committed, positions), BigInt64Array.from([5n, 50n, -1n, 350n]))
+})
+
+test('computeLag should not return negative lag when offsets are ahead of the high watermarks', () => {
+  // High watermarks read before the positions might be stale
+  const highWatermarks = BigInt64Array.from([100n, 200n, 300n])
+  const committed = BigInt64Array.from([90n, 210n, 0n])
+  const positions = BigInt64Array.from([105n, 205n, 300n])
+
+  const lag = computeLag(highWatermarks, committed, positions)
+  deepStrictEqual(lag, BigInt64Array.from([0n, 0n, 0n]))
+
+  const statistics = computeLagStatistics(lag)
+  deepStrictEqual(statistics.partitions, 3)
+  deepStrictEqual(statistics.max, 0n)
+})
+
+test('computeLag should validate the input', () => {
+  const message = 'High watermarks, committed offsets and positions must have the same length.'
+
+  throws(() => computeLag(new BigInt64Array(2), new BigInt64Array(3)), { constructor: UserError, message })
+  throws(() => computeLag(new BigInt64Array(2), new BigInt64Array(2), new BigInt64Array(1)), {
+    constructor: UserError,
+    message
+  })
+})
+
+test('computeLagStatistics should compute aggregate statistics', () => {
+  const lag = new Map([
+    ['topic-1', [10n, -1n, 30n, 20n]],
+    ['topic-2', [40n, -2n, 50n, 0n, 60n, 70n, 80n, 90n]]
+  ])
+
+  const statistics = computeLagStatistics(lag, [50, 90, 100])
+
+  deepStrictEqual(statistics, {
+    partitions: 10,
+    total: 450n,
+    min: 0n,
+    max: 90n,
+    mean: 45,
+    percentiles: new Map([
+      [50, 40n],
+      [90, 80n],
+      [100, 90n]
+    ])
+  })
+
+  deepStrictEqual(computeLagStatistics(BigInt64Array.from([3n, 1n, 2n])).percentiles, new Map([
+    [50, 2n],
+    [90, 3n],
+    [99, 3n]
+  ]))
+})
+
+test('computeLagStatistics should handle empty input', () => {
+  deepStrictEqual(computeLagStatistics([-1n, -2n], [50]), {
+    partitions: 0,
+    total: 0n,
+    min: 0n,
+    max: 0n,
+    mean: 0,
+    percentiles: new Map([[50, 0n]])
+  })
+})
+
+test('computeLagStatistics should validate percentiles', () => {
+  for (const percentile of [0, 101, Number.NaN, Number.POSITIVE_INFINITY]) {
+    throws(() => computeLagStatistics([1n], [percentile]), {
+      constructor: UserError,
+      message: 'Percentiles must be greater than 0 and less than or equal to 100.'
+    })
+  }
+})
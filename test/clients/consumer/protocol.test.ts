@@ -0,0 +1,76 @@
+import { deepStrictEqual } from 'node:assert'
+import test from 'node:test'
+import {
+  decodeConsumerProtocolAssignment,
+  decodeConsumerProtocolSubscription,
+  encodeConsumerProtocolAssignment,
+  encodeConsumerProtocolSubscription,
+  Writer
+} from '../../../src/index.ts'
+
+test('encodeConsumerProtocolSubscription and decodeConsumerProtocolSubscription should roundtrip all versions', () => {
+  const ownedPartitions = [
+    { topic: 'topic-1', partitions: [0, 2] },
+    { topic: 'topic-2', partitions: [1] }
+  ]
+
+  const expected = [
+    { version: 0, topics: ['topic-1', 'topic-2'], userData: Buffer.from('data') },
+    { version: 1, topics: ['topic-1', 'topic-2'], userData: Buffer.from('data'), ownedPartitions },
+    { version: 2, topics: ['topic-1', 'topic-2'], userData: null, ownedPartitions, generationId: 42 },
+    { version: 3, topics: ['topic-1', 'topic-2'], userData: null, ownedPartitions, generationId: 42, rackId: 'rack-1' }
+  ]
+
+  for (const subscription of expected) {
+    deepStrictEqual(decodeConsumerProtocolSubscription(encodeConsumerProtocolSubscription(subscription)), subscription)
+  }
+})
+
+test('encodeConsumerProtocolSubscription should use defaults for missing fields', () => {
+  const buffer = encodeConsumerProtocolSubscription({ version: 3, topics: ['topic-1'], userData: null })
+
+  deepStrictEqual(decodeConsumerProtocolSubscription(buffer), {
+    version: 3,
+    topics: ['topic-1'],
+    userData: null,
+    ownedPartitions: [],
+    generationId: -1,
+    rackId: null
+  })
+})
+
+test('decodeConsumerProtocolSubscription should accept subscriptions missing newer fields', () => {
+  const buffer = Writer.create()
+    .appendInt16(1)
+    .appendArray(['topic-1'], (w, t) => w.appendString(t, false), false, false)
+    .appendBytes(null, false).buffer
+
+  deepStrictEqual(decodeConsumerProtocolSubscription(buffer), { version: 1, topics: ['topic-1'], userData: null })
+})
+
+test('encodeConsumerProtocolAssignment and decodeConsumerProtocolAssignment should roundtrip', () => {
+  const assignment = {
+    version: 0,
+    assignedPartitions: [
+      { topic: 'topic-1', partitions: [0, 1, 2] },
+      { topic: 'topic-2', partitions: [] }
+    ],
+    userData: Buffer.from('data')
+  }
+
+  deepStrictEqual(decodeConsumerProtocolAssignment(encodeConsumerProtocolAssignment(assignment)), assignment)
+})
+
+test('decodeConsumerProtocolAssignment should handle empty assignments', () => {
+  deepStrictEqual(decodeConsumerProtocolAssignment(Buffer.alloc(0)), {
+    version: 0,
+    assignedPartitions: [],
+    userData: null
+  })
+
+  deepStrictEqual(decodeConsumerProtocolAssignment(Writer.create().appendInt16(1).buffer), {
+    version: 1,
+    assignedPartitions: [],
+    userData: null
+  })
+})
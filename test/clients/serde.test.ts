@@ -1,4 +1,4 @@
-import { deepStrictEqual, strictEqual } from 'node:assert'
+import { deepStrictEqual, strictEqual, throws } from 'node:assert'
 import { test } from 'node:test'
 import {
   deserializersFrom,
@@ -8,7 +8,12 @@ import {
   stringDeserializer,
   stringDeserializers,
   stringSerializer,
-  stringSerializers
+  stringSerializers,
+  strictStringDeserializer,
+  strictStringDeserializers,
+  strictStringSerializer,
+  strictStringSerializers,
+  UserError
 } from '../../src/index.ts'
 
 test('stringSerializer converts string to Buffer', () => {
@@ -184,3 +189,34 @@ test('stringDeserializers is exported and contains string deserializer functions
   const result = stringDeserializers.key(Buffer.from('test'))
   strictEqual(result, 'test')
 })
+
+test('strictStringSerializer converts well formed strings to Buffer', () => {
+  deepStrictEqual(strictStringSerializer('hello 👋'), Buffer.from('hello 👋', 'utf-8'))
+  strictEqual(strictStringSerializer(123 as unknown as string), undefined)
+})
+
+test('strictStringSerializer rejects strings with lone surrogates', () => {
+  throws(() => strictStringSerializer('invalid \ud83d'), {
+    constructor: UserError,
+    message: 'Cannot serialize a string which is not well formed UTF-16.'
+  })
+})
+
+test('strictStringDeserializer converts valid UTF-8 buffers to string', () => {
+  strictEqual(strictStringDeserializer(Buffer.from('hello 👋', 'utf-8')), 'hello 👋')
+  strictEqual(strictStringDeserializer('string' as unknown as Buffer), undefined)
+})
+
+test('strictStringDeserializer rejects invalid UTF-8 buffers', () => {
+  throws(() => strictStringDeserializer(Buffer.from([0x68, 0x69, 0xc3, 0x28])), {
+    constructor: UserError,
+    message: 'Cannot deserialize a buffer which is not valid UTF-8.'
+  })
+})
+
+test('strictStringSerializers and strictStringDeserializers are exported', () => {
+  for (const key of ['key', 'value', 'headerKey', 'headerValue'] as const) {
+    strictEqual(strictStringSerializers[key], strictStringSerializer)
+    strictEqual(strictStringDeserializers[key], strictStringDeserializer)
+  }
+})
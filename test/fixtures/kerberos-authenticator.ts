@@ -1,11 +1,11 @@
-import krb, { type KerberosClient } from 'kerberos'
+import krb from 'kerberos'
 import { execSync } from 'node:child_process'
 import { mkdtemp, rm, writeFile } from 'node:fs/promises'
 import { tmpdir } from 'node:os'
 import { resolve as resolvePaths } from 'node:path'
 import {
   AuthenticationError,
-  EMPTY_BUFFER,
+  saslGssapi,
   saslUtils,
   type Callback,
   type CallbackWithPromise,
@@ -17,70 +17,6 @@ import {
 } from '../../src/index.ts'
 
 type SaslAuthenticateResponse = saslAuthenticateV2.SaslAuthenticateResponse
-type SASLAuthenticationAPI = saslAuthenticateV2.SASLAuthenticationAPI
-
-function createKerberosAuthenticationError (message: string, kerberosError: string): AuthenticationError {
-  return new AuthenticationError(message, { kerberosError })
-}
-
-function performChallenge (
-  connection: Connection,
-  authenticate: SASLAuthenticationAPI,
-  client: KerberosClient,
-  step: string,
-  callback: CallbackWithPromise<SaslAuthenticateResponse>
-): void {
-  client.step(step, async (error, challenge) => {
-    if (error) {
-      callback(createKerberosAuthenticationError('Cannot continue Kerberos step challenge.', error))
-      return
-    }
-
-    const challengeBuffer = challenge ? Buffer.from(challenge, 'base64') : EMPTY_BUFFER
-
-    authenticate(connection, challengeBuffer, (error, response) => {
-      if (error) {
-        callback(new AuthenticationError('SASL authentication failed.', { cause: error }))
-        return
-      }
-
-      if (response!.authBytes.length === 0) {
-        callback(null, response)
-        return
-      }
-
-      if (client.contextComplete) {
-        client.unwrap(response!.authBytes.toString('base64'), error => {
-          if (error) {
-            callback(createKerberosAuthenticationError('Cannot unwrap Kerberose response', error))
-            return
-          }
-
-          // Byte 0: No security layer; Byte 1-3: max message size - 0=none
-          client.wrap(Buffer.from([1, 0, 0, 0]).toString('base64'), {}, (error, wrapped) => {
-            if (error) {
-              callback(createKerberosAuthenticationError('Cannot wrap Kerberos response.', error))
-              return
-            }
-
-            authenticate(connection, Buffer.from(wrapped, 'base64'), (error, response) => {
-              if (error) {
-                callback(new AuthenticationError('SASL authentication failed.', { cause: error }))
-                return
-              }
-
-              callback(null, response)
-            })
-          })
-        })
-
-        return
-      }
-
-      performChallenge(connection, authenticate, client, response!.authBytes.toString('base64'), callback)
-    })
-  })
-}
 
 async function restoreEnvironment (
   callback: Callback<SaslAuthenticateResponse>,
@@ -160,11 +96,11 @@ async function authenticate (
 
     krb.initializeClient(service, {}, (error, client) => {
       if (error) {
-        callback(createKerberosAuthenticationError('Cannot initialize Kerberos client.', error))
+        callback(new AuthenticationError('Cannot initialize Kerberos client.', { kerberosError: error }))
         return
       }
 
-      performChallenge(connection, authenticate, client, '', afterRestoreCallback)
+      saslGssapi.authenticate(authenticate, connection, client, afterRestoreCallback)
     })
   } catch (error) {
     await afterRestoreCallback(error)
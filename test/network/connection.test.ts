@@ -17,6 +17,7 @@ import {
   type ConnectionDiagnosticEvent,
   connectionsApiChannel,
   connectionsConnectsChannel,
+  connectionsSaslChannel,
   ConnectionStatuses,
   type CredentialProvider,
   instancesChannel,
@@ -24,13 +25,16 @@ import {
   NetworkError,
   parseBroker,
   type Reader,
+  type ResponseError,
   saslHandshakeV1,
   SASLMechanisms,
+  type SASLAuthenticationProgress,
   type SASLMechanismValue,
   saslOAuthBearer,
   type SASLOptions,
   saslPlain,
   saslScramSha,
+  TimeoutError,
   UnexpectedCorrelationIdError,
   UserError,
   Writer
@@ -1223,6 +1227,42 @@ for (const mechanism of allowedSASLMechanisms) {
   })
 }
 
+test('Connection.connect should time out when the SASL authentication never completes', async t => {
+  let authenticatorCalls = 0
+
+  const connection = new Connection('clientId', {
+    connectTimeout: 5000,
+    sasl: {
+      mechanism: 'PLAIN',
+      timeout: 200,
+      // Simulate an authenticator which never calls back, like one waiting for an unreachable KDC
+      authenticate () {
+        authenticatorCalls++
+      }
+    }
+  })
+  t.after(() => connection.close())
+
+  let timeoutEvent: Error | undefined
+  connection.on('timeout', error => {
+    timeoutEvent = error
+  })
+
+  const startedAt = Date.now()
+
+  await rejects(() => connection.connect(saslBroker.host, saslBroker.port) as Promise<unknown>, (error: any) => {
+    ok(error instanceof TimeoutError)
+    strictEqual(error.message, `SASL authentication to ${saslBroker.host}:${saslBroker.port} timed out.`)
+    strictEqual(error.saslStep, 0)
+    ok(error.saslTraceId)
+    return true
+  })
+
+  ok(Date.now() - startedAt < 5000)
+  strictEqual(authenticatorCalls, 1)
+  ok(timeoutEvent instanceof TimeoutError)
+})
+
 test('Connection.connect should reject unsupported mechanisms', async () => {
   const connection = new Connection('clientId', {
     // @ts-expect-error - Purposefully using an invalid mechanism
@@ -1272,6 +1312,107 @@ test('Connection.connect should handle authentication errors', async t => {
   }
 })
 
+test('Connection.connect should tag authentication errors with the SASL trace', async t => {
+  const connection = new Connection('clientId', {
+    sasl: { mechanism: SASLMechanisms.SCRAM_SHA_256, username: 'admin', password: 'invalid' }
+  })
+  t.after(() => connection.close())
+
+  try {
+    await connection.connect(saslBroker.host, saslBroker.port)
+    throw new Error('Expected error not thrown')
+  } catch (error) {
+    ok(error instanceof NetworkError)
+    deepStrictEqual(error.saslTraceId, `${connection.instanceId}-1`)
+    deepStrictEqual(error.saslStep, 2)
+  }
+})
+
+test('Connection.connect should emit progress events for each SASL authentication step', async t => {
+  const connection = new Connection('clientId', {
+    sasl: { mechanism: SASLMechanisms.SCRAM_SHA_256, username: 'admin', password: 'admin' }
+  })
+  t.after(() => connection.close())
+
+  const steps: SASLAuthenticationProgress[] = []
+  connection.on('sasl:authentication:step', progress => steps.push(progress))
+
+  await connection.connect(saslBroker.host, saslBroker.port)
+
+  deepStrictEqual(
+    steps.map(({ traceId, mechanism, step }) => ({ traceId, mechanism, step })),
+    [
+      { traceId: `${connection.instanceId}-1`, mechanism: SASLMechanisms.SCRAM_SHA_256, step: 1 },
+      { traceId: `${connection.instanceId}-1`, mechanism: SASLMechanisms.SCRAM_SHA_256, step: 2 }
+    ]
+  )
+
+  for (const { elapsed } of steps) {
+    ok(elapsed >= 0)
+  }
+})
+
+test('Connection.connect should support diagnostic channels when authenticating', async t => {
+  const connection = new Connection('clientId', {
+    sasl: { mechanism: SASLMechanisms.PLAIN, username: 'admin', password: 'admin' }
+  })
+  t.after(() => connection.close())
+
+  const verifyTracingChannel = createTracingChannelVerifier(
+    connectionsSaslChannel,
+    ['connection', 'result'],
+    {
+      start (context: ConnectionDiagnosticEvent) {
+        deepStrictEqual(context, {
+          operationId: mockedOperationId,
+          connection,
+          operation: 'authenticate',
+          host: saslBroker.host,
+          port: saslBroker.port,
+          mechanism: SASLMechanisms.PLAIN,
+          reauthenticating: false,
+          traceId: `${connection.instanceId}-1`,
+          steps: 0
+        })
+      },
+      asyncStart (context: ConnectionDiagnosticEvent) {
+        deepStrictEqual(context.steps, 1)
+        ok(Buffer.isBuffer((context.result as SaslAuthenticateResponse).authBytes))
+      },
+      error (context: ConnectionDiagnosticEvent) {
+        ok(typeof context === 'undefined')
+      }
+    },
+    (_, context) => context.connection === connection
+  )
+
+  await connection.connect(saslBroker.host, saslBroker.port)
+
+  verifyTracingChannel()
+})
+
+test('Connection.connect should support diagnostic channels when authentication fails', async t => {
+  const connection = new Connection('clientId', {
+    sasl: { mechanism: SASLMechanisms.PLAIN, username: 'admin', password: 'invalid' }
+  })
+  t.after(() => connection.close())
+
+  const verifyTracingChannel = createTracingChannelVerifier(
+    connectionsSaslChannel,
+    ['connection', 'error'],
+    {
+      error (context: ConnectionDiagnosticEvent) {
+        deepStrictEqual((context.error as ResponseError).errors[0].apiId, 'SASL_AUTHENTICATION_FAILED')
+      }
+    },
+    (_, context) => context.connection === connection
+  )
+
+  await rejects(() => connection.connect(saslBroker.host, saslBroker.port))
+
+  verifyTracingChannel()
+})
+
 test('Connection.connect should connect to a TLS host without forwarding the servername', async t => {
   const { server, port } = await createTLSServer(t)
 
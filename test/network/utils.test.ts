@@ -0,0 +1,36 @@
+import { deepStrictEqual, throws } from 'node:assert'
+import test from 'node:test'
+import { parseBroker, UserError } from '../../src/index.ts'
+
+test('parseBroker should parse hostnames and IPv4 addresses', () => {
+  deepStrictEqual(parseBroker('localhost:9093'), { host: 'localhost', port: 9093 })
+  deepStrictEqual(parseBroker('127.0.0.1:9093'), { host: '127.0.0.1', port: 9093 })
+  deepStrictEqual(parseBroker('localhost'), { host: 'localhost', port: 9092 })
+  deepStrictEqual(parseBroker('localhost', 19092), { host: 'localhost', port: 19092 })
+})
+
+test('parseBroker should parse IPv6 addresses', () => {
+  deepStrictEqual(parseBroker('[::1]:9093'), { host: '::1', port: 9093 })
+  deepStrictEqual(parseBroker('[2001:db8::1]'), { host: '2001:db8::1', port: 9092 })
+  deepStrictEqual(parseBroker('2001:db8::1'), { host: '2001:db8::1', port: 9092 })
+  deepStrictEqual(parseBroker('::1', 19092), { host: '::1', port: 19092 })
+})
+
+test('parseBroker should reject malformed IPv6 addresses', () => {
+  throws(() => parseBroker('[::1'), {
+    constructor: UserError,
+    message: 'Invalid broker "[::1": missing closing bracket.'
+  })
+
+  for (const broker of ['[::1]junk', '[::1]:', '[::1]:port', '[::1]:9092:9093', '[::1] :9092']) {
+    throws(() => parseBroker(broker), {
+      constructor: UserError,
+      message: `Invalid broker "${broker}": only a port can follow the closing bracket.`
+    })
+  }
+})
+
+test('parseBroker should return broker objects as they are', () => {
+  const broker = { host: '::1', port: 9093 }
+  deepStrictEqual(parseBroker(broker), broker)
+})
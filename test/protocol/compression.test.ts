@@ -1,9 +1,34 @@
 import { DynamicBuffer } from '@platformatic/dynamic-buffer'
-import { deepStrictEqual, strictEqual, throws } from 'node:assert'
+import { deepStrictEqual, match, ok, rejects, strictEqual, throws } from 'node:assert'
+import { randomBytes } from 'node:crypto'
 import { platform } from 'node:os'
 import test from 'node:test'
 import zlib from 'node:zlib'
-import { compressionsAlgorithms, compressionsAlgorithmsByBitmask } from '../../src/index.ts'
+import {
+  allowedCompressionsAlgorithms,
+  compressionsAlgorithms,
+  compressionsAlgorithmsByBitmask,
+  compressionsAlgorithmsNamesByBitmask,
+  createRecordsBatch,
+  customCompressionsAlgorithms,
+  createStreamingCompressor,
+  lz4BlockCompressSync,
+  lz4BlockDecompressSync,
+  produceOptionsValidator,
+  Reader,
+  readRecordsBatch,
+  registerCompressionAlgorithm,
+  StreamingCompressor,
+  unregisterCompressionAlgorithm,
+  UnsupportedCompressionError,
+  UserError
+} from '../../src/index.ts'
+
+declare module '../../src/protocol/compression.ts' {
+  interface CustomCompressionAlgorithms {
+    reverse: true
+  }
+}
 
 test('compressionsAlgorithms contains expected algorithms', () => {
   strictEqual(typeof compressionsAlgorithms.gzip, 'object')
@@ -75,6 +100,114 @@ test('lz4 compression works correctly', () => {
   strictEqual(decompressed.toString(), 'test data for compression')
 })
 
+test('lz4 block compression roundtrips', () => {
+  const inputs = [
+    Buffer.alloc(0),
+    Buffer.from('a'),
+    Buffer.from('test data for compression'),
+    Buffer.from('test data for compression'.repeat(500)),
+    Buffer.from(Array.from({ length: 5000 }, (_, i) => (i * 7919) % 251)),
+    Buffer.concat([Buffer.from('header'), Buffer.alloc(70000), Buffer.from('trailer')])
+  ]
+
+  for (const input of inputs) {
+    const compressed = lz4BlockCompressSync(input)
+
+    deepStrictEqual(lz4BlockDecompressSync(compressed), input)
+    deepStrictEqual(lz4BlockDecompressSync(compressed, input.length), input)
+  }
+
+  ok(lz4BlockCompressSync(Buffer.from('test data for compression'.repeat(500))).length < 200)
+})
+
+test('lz4 block decompression handles overlapping matches', () => {
+  // Literal 'a', then a 20 bytes match at offset 1, then 5 literals
+  const block = Buffer.from([0x1f, 0x61, 0x01, 0x00, 0x01, 0x50, 0x61, 0x61, 0x61, 0x61, 0x61])
+
+  strictEqual(lz4BlockDecompressSync(block).toString(), 'a'.repeat(26))
+})
+
+test('lz4 block decompression validates the input', () => {
+  const compressed = lz4BlockCompressSync(Buffer.from('test data for compression'.repeat(10)))
+
+  throws(() => lz4BlockDecompressSync(compressed, 10), /decompressed data exceeds the uncompressed size/)
+  throws(() => lz4BlockDecompressSync(compressed, 1000), /decompressed data is shorter than the uncompressed size/)
+  throws(() => lz4BlockDecompressSync(Buffer.from([0xf0, 0x01, 0x61])), /literals exceed the block length/)
+  throws(() => lz4BlockDecompressSync(Buffer.from([0x10, 0x61, 0x05, 0x00])), /match offset out of range/)
+  throws(
+    () => lz4BlockDecompressSync(Buffer.from([0x10, 0x61, 0x01, 0x00])),
+    /last sequence must only contain literals/
+  )
+})
+
+test('lz4 block decompression validates the uncompressed size', () => {
+  const compressed = lz4BlockCompressSync(Buffer.from('test data for compression'))
+
+  const sizes = [
+    -1,
+    1.5,
+    Number.NaN,
+    Number.POSITIVE_INFINITY,
+    Number.MAX_SAFE_INTEGER,
+    compressed.length * 255 + 1,
+    '10' as unknown as number
+  ]
+
+  for (const size of sizes) {
+    throws(() => lz4BlockDecompressSync(compressed, size), { message: 'Invalid LZ4 block: invalid uncompressed size' })
+  }
+
+  deepStrictEqual(lz4BlockDecompressSync(lz4BlockCompressSync(Buffer.alloc(0)), 0), Buffer.alloc(0))
+})
+
+test('lz4 block decompression rejects truncated input', () => {
+  const input = Buffer.from('test data for compression'.repeat(20) + 'a'.repeat(300))
+  const compressed = lz4BlockCompressSync(input)
+
+  throws(() => lz4BlockDecompressSync(Buffer.from([0x00, 0x01])), /truncated match offset/)
+  throws(() => lz4BlockDecompressSync(Buffer.from([0xf0])), /truncated sequence length/)
+  throws(() => lz4BlockDecompressSync(Buffer.from([0xf0, 0xff]), 64), /truncated sequence length/)
+
+  for (let i = 1; i < compressed.length; i++) {
+    try {
+      lz4BlockDecompressSync(compressed.subarray(0, i))
+    } catch (error) {
+      match((error as Error).message, /^Invalid LZ4 block: /)
+    }
+  }
+})
+
+test('lz4 decompression rejects data which is neither a frame nor a block', () => {
+  throws(() => compressionsAlgorithms.lz4.decompressSync(Buffer.from('not lz4 at all')), {
+    message: 'Invalid LZ4 data: not an LZ4 frame nor a valid LZ4 block'
+  })
+
+  // Random data must either decode or fail with a LZ4 error
+  let seed = 42
+  for (let i = 0; i < 1000; i++) {
+    const garbage = Buffer.alloc(1 + (i % 64))
+
+    for (let j = 0; j < garbage.length; j++) {
+      seed = (Math.imul(seed, 1103515245) + 12345) >>> 0
+      garbage[j] = seed >>> 24
+    }
+
+    try {
+      compressionsAlgorithms.lz4.decompressSync(garbage)
+    } catch (error) {
+      match((error as Error).message, /^Invalid LZ4 data: /)
+      match(((error as Error).cause as Error).message, /^Invalid LZ4 block: /)
+    }
+  }
+})
+
+test('lz4 decompression detects frames and blocks', () => {
+  const input = Buffer.from('test data for compression'.repeat(10))
+
+  deepStrictEqual(compressionsAlgorithms.lz4.decompressSync(compressionsAlgorithms.lz4.compressSync(input)), input)
+  deepStrictEqual(compressionsAlgorithms.lz4.decompressSync(lz4BlockCompressSync(input)), input)
+})
+
 test('zstd compression works correctly', { skip: !('zstdCompressSync' in zlib) }, () => {
   const input = Buffer.from('test data for compression')
   const validCompressed = '28b52ffd2019c9000074657374206461746120666f7220636f6d7072657373696f6e'
@@ -93,3 +226,157 @@ test('throws when zstd is not available', { skip: 'zstdCompressSync' in zlib },
     compressionsAlgorithms.zstd.compressSync(data)
   }, /zstd is not supported in the current Node.js version/)
 })
+
+test('registerCompressionAlgorithm allows custom codecs', t => {
+  const reverse = (data: Buffer | DynamicBuffer) => {
+    const buffer = DynamicBuffer.isDynamicBuffer(data) ? (data as DynamicBuffer).buffer : (data as Buffer)
+    return Buffer.from(buffer).reverse()
+  }
+
+  registerCompressionAlgorithm('reverse', { compressSync: reverse, decompressSync: reverse, bitmask: 5 })
+  t.after(() => unregisterCompressionAlgorithm('reverse'))
+
+  strictEqual(customCompressionsAlgorithms.reverse.compressSync, reverse)
+  strictEqual('reverse' in compressionsAlgorithms, false)
+
+  strictEqual(compressionsAlgorithmsNamesByBitmask[5], 'reverse')
+  strictEqual(compressionsAlgorithmsByBitmask[5].available, true)
+  ok(allowedCompressionsAlgorithms.includes('reverse'))
+  strictEqual(produceOptionsValidator({ compression: 'reverse' }), true)
+
+  const batch = createRecordsBatch([{ value: Buffer.from('value'), topic: 'test-topic' }], {
+    producerId: 0n,
+    compression: 'reverse'
+  })
+  const read = readRecordsBatch(Reader.from(batch))
+
+  strictEqual(read.attributes & 0b111, 5)
+  strictEqual(read.records[0].value!.toString(), 'value')
+})
+
+test('registerCompressionAlgorithm validates the codec', t => {
+  const { compressSync, decompressSync } = compressionsAlgorithms.none
+  const specification = { compressSync, decompressSync }
+
+  throws(() => registerCompressionAlgorithm('gzip', { ...specification, bitmask: 6 }), {
+    constructor: UserError,
+    message: 'Compression algorithm gzip is already registered.'
+  })
+
+  throws(() => registerCompressionAlgorithm('custom', { ...specification, bitmask: 3 }), {
+    constructor: UserError,
+    message: 'Compression algorithm bitmask must be one of 5, 6, 7.'
+  })
+
+  registerCompressionAlgorithm('first', { ...specification, bitmask: 7 })
+  t.after(() => unregisterCompressionAlgorithm('first'))
+
+  throws(() => registerCompressionAlgorithm('second', { ...specification, bitmask: 7 }), {
+    constructor: UserError,
+    message: 'Compression algorithm bitmask 7 is already used by first.'
+  })
+
+  // Properties of the prototype are not registered algorithms
+  throws(() => registerCompressionAlgorithm('toString', { ...specification, bitmask: 4 }), {
+    constructor: UserError,
+    message: 'Compression algorithm bitmask must be one of 5, 6, 7.'
+  })
+})
+
+test('unregisterCompressionAlgorithm removes custom codecs', () => {
+  const { compressSync, decompressSync } = compressionsAlgorithms.none
+
+  registerCompressionAlgorithm('temporary', { compressSync, decompressSync, bitmask: 6 })
+  unregisterCompressionAlgorithm('temporary')
+
+  strictEqual(customCompressionsAlgorithms.temporary, undefined)
+  strictEqual(compressionsAlgorithmsByBitmask[6], undefined)
+  strictEqual(compressionsAlgorithmsNamesByBitmask[6], undefined)
+  ok(!(allowedCompressionsAlgorithms as string[]).includes('temporary'))
+  strictEqual(produceOptionsValidator({ compression: 'temporary' }), false)
+
+  // The bitmask can be reused once released
+  registerCompressionAlgorithm('temporary', { compressSync, decompressSync, bitmask: 6 })
+  unregisterCompressionAlgorithm('temporary')
+
+  throws(() => unregisterCompressionAlgorithm('temporary'), {
+    constructor: UserError,
+    message: 'Compression algorithm temporary is not a registered custom algorithm.'
+  })
+
+  throws(() => unregisterCompressionAlgorithm('gzip'), {
+    constructor: UserError,
+    message: 'Compression algorithm gzip is not a registered custom algorithm.'
+  })
+})
+
+test('StreamingCompressor compresses data pushed in chunks', async () => {
+  const chunks = Array.from({ length: 10 }, (_, i) => Buffer.from(`chunk-${i}-`.repeat(1000)))
+
+  const compressor = createStreamingCompressor('gzip')
+  for (const chunk of chunks) {
+    compressor.push(chunk)
+  }
+
+  deepStrictEqual(zlib.gunzipSync(await compressor.finish()), Buffer.concat(chunks))
+})
+
+test('StreamingCompressor allows to read the compressed data as it is produced', async () => {
+  // Random data is not compressible, so output is produced while pushing
+  const chunks = Array.from({ length: 16 }, () => randomBytes(65536))
+
+  const compressor = createStreamingCompressor('gzip')
+  const compressed: Buffer[] = []
+  let partialReads = 0
+
+  for (const chunk of chunks) {
+    if (!compressor.push(chunk)) {
+      await new Promise(resolve => compressor.once('drain', resolve))
+    }
+
+    const data = compressor.read()
+
+    if (data.length > 0) {
+      partialReads++
+      compressed.push(data)
+    }
+  }
+
+  ok(partialReads > 0)
+  deepStrictEqual(compressor.read(), Buffer.alloc(0))
+
+  compressed.push(await compressor.finish())
+  deepStrictEqual(zlib.gunzipSync(Buffer.concat(compressed)), Buffer.concat(chunks))
+})
+
+test('StreamingCompressor supports zstd', { skip: !('createZstdCompress' in zlib) }, (_, done) => {
+  const compressor = new StreamingCompressor('zstd')
+  compressor.push(Buffer.from('first '))
+  compressor.push(new DynamicBuffer(Buffer.from('second')))
+
+  compressor.finish((error, compressed) => {
+    strictEqual(error, null)
+    strictEqual(zlib.zstdDecompressSync(compressed!).toString(), 'first second')
+    done()
+  })
+})
+
+test('StreamingCompressor validates its usage', async () => {
+  throws(() => createStreamingCompressor('lz4' as 'gzip'), {
+    constructor: UnsupportedCompressionError,
+    message: 'Unsupported streaming compression algorithm lz4'
+  })
+
+  const compressor = createStreamingCompressor('gzip')
+  await compressor.finish()
+
+  throws(() => compressor.push(Buffer.from('data')), {
+    constructor: UserError,
+    message: 'Cannot push data to a finished compressor.'
+  })
+
+  await rejects(compressor.finish(), {
+    constructor: UserError,
+    message: 'Compressor has already been finished.'
+  })
+})
@@ -2,7 +2,7 @@ import { DynamicBuffer } from '@platformatic/dynamic-buffer'
 import { crc32c as wasmCRC32C } from '@platformatic/wasm-utils'
 import { deepStrictEqual } from 'node:assert'
 import test from 'node:test'
-import { jsCRC32C, loadNativeCRC32C } from '../../src/index.ts'
+import { crc32cCombine, jsCRC32C, loadNativeCRC32C } from '../../src/index.ts'
 
 // Samples copied from https://github.com/tulios/kafkajs/blob/55b0b416308b9e597a5a6b97b0a6fd6b846255dc/src/protocol/recordBatch/crc32c/fixtures/samples.js
 const samples = [
@@ -104,3 +104,20 @@ for (const [name, implementation] of Object.entries(implementations)) {
     deepStrictEqual(implementation(new DynamicBuffer(Buffer.from('\u0000'))), 1383945041)
   })
 }
+
+test('crc32cCombine should merge the checksums of two chunks', () => {
+  for (const sample of samples) {
+    const buffer = Buffer.from(sample.input)
+
+    for (const split of [0, 1, Math.floor(buffer.length / 2), buffer.length]) {
+      const first = buffer.subarray(0, split)
+      const second = buffer.subarray(split)
+
+      deepStrictEqual(crc32cCombine(jsCRC32C(first), jsCRC32C(second), second.length), sample.output)
+    }
+  }
+})
+
+test('crc32cCombine should return the first checksum when the second chunk is empty', () => {
+  deepStrictEqual(crc32cCombine(818496390, 0, 0), 818496390)
+})
@@ -1,17 +1,25 @@
-import { ok, strictEqual } from 'node:assert'
+import { deepStrictEqual, ok, strictEqual, throws } from 'node:assert'
+import { randomBytes } from 'node:crypto'
 import test from 'node:test'
 import {
+  type CompressionDiagnosticEvent,
   compressionsAlgorithms,
   createRecord,
+  createRecordHeaders,
   createRecordsBatch,
+  estimateBatchSize,
   NumericMap,
+  protocolCompressionsChannel,
   Reader,
   readRecord,
   readRecordsBatch,
+  UnsupportedCompressionError,
+  UserError,
   Writer,
   type CompressionAlgorithmSpecification,
   type MessageRecord
 } from '../../src/index.ts'
+import { createTracingChannelVerifier, mockedOperationId } from '../helpers.ts'
 
 test('createRecord should create a buffer with the correct format', () => {
   const timestamp = BigInt(1720000000000) // Fixed timestamp for testing
@@ -128,6 +136,110 @@ test('readRecord should correctly parse a record buffer', () => {
   strictEqual(record.headers[1][1]!.toString(), 'value2', 'Second header value should match')
 })
 
+test('createRecordHeaders should encode headers as varint framed pairs', () => {
+  const headers = new Map([
+    [Buffer.from('header1'), Buffer.from('value1')],
+    [Buffer.from('header2'), Buffer.from('x'.repeat(200))]
+  ])
+
+  const expected = Writer.create().appendVarIntMap(headers, (w, [key, value]) => {
+    w.appendVarIntBytes(key).appendVarIntBytes(value)
+  }).buffer
+
+  deepStrictEqual(createRecordHeaders(headers), expected)
+  deepStrictEqual(createRecordHeaders(Array.from(headers).flat()), expected)
+
+  const reader = Reader.from(createRecordHeaders([Buffer.from('key'), null]))
+  deepStrictEqual(
+    reader.readVarIntArray(r => [r.readVarIntBytes(), r.readVarIntBytes()]),
+    [[Buffer.from('key'), null]]
+  )
+})
+
+test('createRecordHeaders should handle missing headers', () => {
+  deepStrictEqual(createRecordHeaders(undefined), Buffer.from([0]))
+  deepStrictEqual(createRecordHeaders(new Map()), Buffer.from([0]))
+  deepStrictEqual(createRecordHeaders([]), Buffer.from([0]))
+
+  // Records without headers share the same buffer
+  strictEqual(createRecordHeaders(null), createRecordHeaders(new Map()))
+})
+
+test('createRecordHeaders should reject unpaired entries', () => {
+  throws(() => createRecordHeaders([Buffer.from('key')]), {
+    constructor: UserError,
+    message: 'Record headers must contain an even number of keys and values.'
+  })
+})
+
+test('estimateBatchSize should compute the size of the batch without encoding it', () => {
+  const timestamp = BigInt(Date.now())
+  const messages: MessageRecord[] = [
+    { value: Buffer.from('value'), topic: 'test-topic' },
+    { key: Buffer.from('key'), value: Buffer.from('x'.repeat(1000)), topic: 'test-topic', timestamp },
+    {
+      key: Buffer.from('key'),
+      value: Buffer.from('value'),
+      headers: new Map([[Buffer.from('header'), Buffer.from('y'.repeat(200))]]),
+      topic: 'test-topic',
+      timestamp: timestamp + 100000n
+    }
+  ]
+
+  const { size, compressedSize } = estimateBatchSize(messages)
+
+  strictEqual(size, createRecordsBatch(messages).length)
+  strictEqual(compressedSize, size)
+})
+
+test('estimateBatchSize should approximate the compressed size', () => {
+  const messages: MessageRecord[] = [{ value: Buffer.from('x'.repeat(1000)), topic: 'test-topic' }]
+
+  const { size, compressedSize } = estimateBatchSize(messages, 'gzip', 0.5)
+  const recordsSize = size - 61
+
+  strictEqual(compressedSize, 61 + Math.ceil(recordsSize * 0.5) + compressionsAlgorithms.gzip.maxOverhead(recordsSize))
+  strictEqual(estimateBatchSize(messages, 'none').compressedSize, size)
+})
+
+test('estimateBatchSize should return an upper bound of the compressed size with a ratio of 1', () => {
+  // Random data cannot be compressed
+  const messages: MessageRecord[] = Array.from({ length: 10 }, () => ({
+    key: randomBytes(16),
+    value: randomBytes(20000),
+    topic: 'test-topic'
+  }))
+
+  for (const [name, algorithm] of Object.entries(compressionsAlgorithms)) {
+    if (name === 'none' || !algorithm.available) {
+      continue
+    }
+
+    const compression = name as keyof typeof compressionsAlgorithms
+    const actual = createRecordsBatch(messages, { compression }).length
+    const { size, compressedSize } = estimateBatchSize(messages, compression, 1)
+
+    ok(compressedSize >= actual, `${name} estimate ${compressedSize} is lower than ${actual}`)
+    ok(compressedSize > size)
+  }
+})
+
+test('estimateBatchSize should require a compression ratio when using compression', () => {
+  const messages: MessageRecord[] = [{ value: Buffer.from('value'), topic: 'test-topic' }]
+
+  for (const ratio of [undefined, 0, -1, Number.NaN, Number.POSITIVE_INFINITY]) {
+    throws(() => estimateBatchSize(messages, 'gzip', ratio as number), {
+      constructor: UserError,
+      message: 'Compression ratio must be a positive number when using compression.'
+    })
+  }
+
+  throws(() => estimateBatchSize(messages, 'unknown' as 'gzip', 1), {
+    constructor: UnsupportedCompressionError,
+    message: 'Unsupported compression algorithm unknown'
+  })
+})
+
 test('createRecordsBatch should create a batch with the correct format', () => {
   const messages: MessageRecord[] = [
     {
@@ -385,6 +497,44 @@ test('readRecordsBatch should handle records with compression', () => {
   strictEqual(batch.records[1].value!.toString(), 'compressed2', 'Second record value should match')
 })
 
+test('createRecordsBatch and readRecordsBatch should support diagnostic channels when using compression', () => {
+  const messages: MessageRecord[] = [{ value: Buffer.from('traced'.repeat(10)), topic: 'test-topic' }]
+
+  const verifyTracingChannel = createTracingChannelVerifier(
+    protocolCompressionsChannel,
+    'result',
+    {
+      start (events: CompressionDiagnosticEvent[]) {
+        deepStrictEqual(
+          events.map(({ operationId, algorithm, operation }) => ({ operationId, algorithm, operation })),
+          [
+            { operationId: mockedOperationId, algorithm: 'gzip', operation: 'compress' },
+            { operationId: mockedOperationId, algorithm: 'gzip', operation: 'decompress' }
+          ]
+        )
+
+        ok((events[0].size as number) > 0)
+        ok((events[1].size as number) > 0)
+      },
+      end (events: CompressionDiagnosticEvent[]) {
+        strictEqual(events.length, 2)
+        ok(Buffer.isBuffer(events[0].result))
+        ok(Buffer.isBuffer(events[1].result))
+      },
+      error (events: CompressionDiagnosticEvent[] | undefined) {
+        strictEqual(events, undefined)
+      }
+    },
+    () => true,
+    true
+  )
+
+  const batch = readRecordsBatch(Reader.from(createRecordsBatch(messages, { producerId: 0n, compression: 'gzip' })))
+  strictEqual(batch.records[0].value!.toString(), 'traced'.repeat(10))
+
+  verifyTracingChannel()
+})
+
 test('readRecordsBatch should throw on unsupported compression bitmask', () => {
   // Create a mock batch with an invalid compression bitmask
   const writer = Writer.create()
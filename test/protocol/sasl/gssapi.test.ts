@@ -0,0 +1,166 @@
+import { deepStrictEqual, ok, rejects, strictEqual } from 'node:assert'
+import test from 'node:test'
+import { type SaslAuthenticateResponse } from '../../../src/apis/security/sasl-authenticate-v2.ts'
+import {
+  AuthenticationError,
+  type CallbackWithPromise,
+  type Connection,
+  type saslAuthenticateV2,
+  saslGssapi
+} from '../../../src/index.ts'
+
+function createResponse (authBytes: Buffer): SaslAuthenticateResponse {
+  return { errorCode: 0, errorMessage: null, authBytes, sessionLifetimeMs: 3600000n }
+}
+
+function createClient (steps: number, failures: { step?: string; unwrap?: string; wrap?: string } = {}) {
+  const calls: string[] = []
+
+  const client: saslGssapi.GSSAPIClient = {
+    contextComplete: false,
+    step (challenge, callback) {
+      calls.push(`step:${challenge}`)
+
+      if (failures.step) {
+        callback(failures.step)
+        return
+      }
+
+      client.contextComplete = calls.filter(c => c.startsWith('step:')).length === steps
+      callback(null, Buffer.from(`token-${calls.length}`).toString('base64'))
+    },
+    unwrap (challenge, callback) {
+      calls.push(`unwrap:${Buffer.from(challenge, 'base64').toString()}`)
+      callback(failures.unwrap ?? null, '')
+    },
+    wrap (challenge, _, callback) {
+      calls.push(`wrap:${Buffer.from(challenge, 'base64').toString('hex')}`)
+
+      if (failures.wrap) {
+        callback(failures.wrap)
+        return
+      }
+
+      callback(null, Buffer.from('wrapped').toString('base64'))
+    }
+  }
+
+  return { client, calls }
+}
+
+function createAPI (replies: Buffer[], error?: Error, failAt: number = 1) {
+  const payloads: string[] = []
+
+  function api (_: Connection, payload: Buffer, callback: CallbackWithPromise<SaslAuthenticateResponse>) {
+    payloads.push(payload.toString())
+
+    if (error && payloads.length === failAt) {
+      callback(error)
+      return
+    }
+
+    callback(null, createResponse(replies.shift() ?? Buffer.alloc(0)))
+  }
+
+  return { api: api as unknown as saslAuthenticateV2.SASLAuthenticationAPI, payloads }
+}
+
+test('authenticate should drive the GSSAPI handshake until completion - promise', async () => {
+  const { client, calls } = createClient(2)
+  const { api, payloads } = createAPI([Buffer.from('challenge-1'), Buffer.from('challenge-2'), Buffer.from('done')])
+
+  const response = await saslGssapi.authenticate(api, {} as Connection, client)
+
+  deepStrictEqual(calls, [
+    'step:',
+    `step:${Buffer.from('challenge-1').toString('base64')}`,
+    'unwrap:challenge-2',
+    'wrap:01000000'
+  ])
+  deepStrictEqual(payloads, ['token-1', 'token-2', 'wrapped'])
+  deepStrictEqual(response.authBytes, Buffer.from('done'))
+})
+
+test('authenticate should drive the GSSAPI handshake until completion - callback', (_, done) => {
+  const { client } = createClient(1)
+  const { api, payloads } = createAPI([Buffer.from('challenge-1')])
+
+  saslGssapi.authenticate(api, {} as Connection, client, (error, response) => {
+    strictEqual(error, null)
+    deepStrictEqual(payloads, ['token-1', 'wrapped'])
+    deepStrictEqual(response!.authBytes, Buffer.alloc(0))
+    done()
+  })
+})
+
+test('authenticate should complete when the broker returns no bytes', async () => {
+  const { client, calls } = createClient(1)
+  const { api } = createAPI([])
+
+  await saslGssapi.authenticate(api, {} as Connection, client)
+
+  deepStrictEqual(calls, ['step:'])
+})
+
+test('authenticate should handle Kerberos errors', async () => {
+  for (const [failure, message, stage] of [
+    ['step', 'Cannot continue Kerberos step challenge.', saslGssapi.GSSAPIStages.HANDSHAKE],
+    ['unwrap', 'Cannot unwrap Kerberos response.', saslGssapi.GSSAPIStages.SECURITY_LAYER],
+    ['wrap', 'Cannot wrap Kerberos response.', saslGssapi.GSSAPIStages.SECURITY_LAYER]
+  ]) {
+    const { client } = createClient(1, { [failure]: 'KRB5 failure' })
+    const { api } = createAPI([Buffer.from('challenge-1')])
+
+    await rejects(saslGssapi.authenticate(api, {} as Connection, client), error => {
+      ok(error instanceof AuthenticationError)
+      strictEqual(error.message, message)
+      strictEqual(error.kerberosError, 'KRB5 failure')
+      strictEqual(error.gssapiStage, stage)
+      strictEqual(error.gssapiOperation, failure)
+      return true
+    })
+  }
+})
+
+test('authenticate should handle broker errors', async () => {
+  const { client } = createClient(1)
+  const cause = new Error('Broker failure')
+  const { api } = createAPI([], cause)
+
+  await rejects(saslGssapi.authenticate(api, {} as Connection, client), error => {
+    ok(error instanceof AuthenticationError)
+    strictEqual(error.message, 'SASL authentication failed.')
+    strictEqual(error.cause, cause)
+    strictEqual(error.gssapiStage, saslGssapi.GSSAPIStages.HANDSHAKE)
+    strictEqual(error.gssapiOperation, 'authenticate')
+    return true
+  })
+})
+
+test('authenticate should report the stage of broker errors during the security layer negotiation', async () => {
+  const { client } = createClient(1)
+  const cause = new Error('Broker failure')
+  const { api } = createAPI([Buffer.from('challenge-1')], cause, 2)
+
+  await rejects(saslGssapi.authenticate(api, {} as Connection, client), error => {
+    ok(error instanceof AuthenticationError)
+    strictEqual(error.cause, cause)
+    strictEqual(error.gssapiStage, saslGssapi.GSSAPIStages.SECURITY_LAYER)
+    strictEqual(error.gssapiOperation, 'authenticate')
+    return true
+  })
+})
+
+test('authenticate should chain Kerberos errors as cause', async () => {
+  const kerberosError = new Error('KRB5 failure')
+  const { client } = createClient(1)
+  client.step = (_, callback) => callback(kerberosError)
+  const { api } = createAPI([])
+
+  await rejects(saslGssapi.authenticate(api, {} as Connection, client), error => {
+    ok(error instanceof AuthenticationError)
+    strictEqual(error.cause, kerberosError)
+    strictEqual(error.kerberosError, kerberosError)
+    return true
+  })
+})
@@ -10,7 +10,9 @@ import {
   humanize,
   listErrorMessage,
   niceJoin,
-  setDebugDumpLogger
+  setDebugDumpLogger,
+  validateGroupIds,
+  validateTopicNames
 } from '../src/index.ts'
 
 test('ajv setup', () => {
@@ -211,3 +213,40 @@ test('NumericMap.postIncrement increments and returns old value', () => {
   strictEqual(map.postIncrement('key3', 1, 10), 5)
   strictEqual(map.get('key3'), 6)
 })
+
+test('validateTopicNames', () => {
+  deepStrictEqual(validateTopicNames(['valid-topic', 'valid.topic_1', 'a'.repeat(249)]), [])
+
+  deepStrictEqual(
+    validateTopicNames(['', '.', '..', 'a'.repeat(250), 'invalid topic', 'topic.name', 'topic_name', 'topic.name']),
+    [
+      { index: 0, name: '', reason: 'Topic name cannot be empty.' },
+      { index: 1, name: '.', reason: 'Topic name cannot be "." or "..".' },
+      { index: 2, name: '..', reason: 'Topic name cannot be "." or "..".' },
+      { index: 3, name: 'a'.repeat(250), reason: 'Topic name cannot be longer than 249 characters.' },
+      {
+        index: 4,
+        name: 'invalid topic',
+        reason: 'Topic name can only contain ASCII alphanumerics, ".", "_" and "-".'
+      },
+      {
+        index: 6,
+        name: 'topic_name',
+        reason: 'Topic name collides with "topic.name" since "." and "_" are equivalent in metrics names.'
+      },
+      { index: 7, name: 'topic.name', reason: 'Topic name is duplicated at index 5.' }
+    ]
+  )
+})
+
+test('validateTopicNames reports duplicated names', () => {
+  deepStrictEqual(validateTopicNames(['orders', 'payments', 'orders', 'orders']), [
+    { index: 2, name: 'orders', reason: 'Topic name is duplicated at index 0.' },
+    { index: 3, name: 'orders', reason: 'Topic name is duplicated at index 0.' }
+  ])
+})
+
+test('validateGroupIds', () => {
+  deepStrictEqual(validateGroupIds(['group', 'another group']), [])
+  deepStrictEqual(validateGroupIds(['group', '']), [{ index: 1, name: '', reason: 'Group ID cannot be empty.' }])
+})